@@ -0,0 +1,179 @@
+use crate::registry::{self, Day};
+use anyhow::Result;
+use log::{debug, trace};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use util::{Input, Part};
+
+const CACHE_FILE: &str = "aoc-cache";
+
+/// Identifies a solve by the bytes it depends on: the day's input file and its built binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    day: u8,
+    part: Part,
+    input: u64,
+    build: u64,
+}
+
+impl Key {
+    /// Returns `None` if the input or binary can't be read, in which case nothing is cached.
+    pub fn new(day: Day, part: Part, input: Input) -> Option<Self> {
+        let input = fs::read(day.dir().join(input.path())).ok()?;
+        let build = fs::read(day.binary()).ok()?;
+
+        Some(Self {
+            day: day.day,
+            part,
+            input: fnv1a(&input),
+            build: fnv1a(&build),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// Previously computed answers, stored in the target directory as one tab-separated entry per
+/// line.
+#[derive(Debug, Default)]
+pub struct Cache {
+    path: PathBuf,
+    entries: HashMap<Key, Entry>,
+}
+
+impl Cache {
+    pub fn load() -> Self {
+        let path = registry::target_dir().join(CACHE_FILE);
+
+        let entries = fs::read_to_string(&path)
+            .map(|s| s.lines().filter_map(parse_entry).collect())
+            .unwrap_or_default();
+        debug!("Loaded {:?} from {}", entries, path.display());
+
+        Self { path, entries }
+    }
+
+    pub fn get(&self, key: &Key) -> Option<&Entry> {
+        self.entries.get(key)
+    }
+
+    /// Caches an answer, replacing any made by an older build for the same input.
+    pub fn insert(&mut self, key: Key, entry: Entry) {
+        self.entries
+            .retain(|k, _| (k.day, k.part, k.input) != (key.day, key.part, key.input));
+        self.entries.insert(key, entry);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let mut entries = self
+            .entries
+            .iter()
+            .map(|(k, e)| format_entry(k, e))
+            .collect::<Vec<_>>();
+        entries.sort_unstable();
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, entries.concat())?;
+        trace!("Saved {} entries to {}", entries.len(), self.path.display());
+
+        Ok(())
+    }
+}
+
+fn format_entry(key: &Key, entry: &Entry) -> String {
+    format!(
+        "{}\t{}\t{:016x}\t{:016x}\t{}\t{}\n",
+        key.day,
+        key.part,
+        key.input,
+        key.build,
+        entry.elapsed.as_nanos(),
+        entry.answer
+    )
+}
+
+fn parse_entry(line: &str) -> Option<(Key, Entry)> {
+    let mut fields = line.splitn(6, '\t');
+
+    let key = Key {
+        day: u8::from_str(fields.next()?).ok()?,
+        part: Part::from_str(fields.next()?).ok()?,
+        input: u64::from_str_radix(fields.next()?, 16).ok()?,
+        build: u64::from_str_radix(fields.next()?, 16).ok()?,
+    };
+    let elapsed = Duration::from_nanos(u64::from_str(fields.next()?).ok()?);
+    let answer = fields.next()?.to_string();
+
+    Some((key, Entry { answer, elapsed }))
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(0xcbf29ce484222325, fnv1a(b""));
+        assert_eq!(0xaf63dc4c8601ec8c, fnv1a(b"a"));
+    }
+
+    #[test]
+    fn test_entry_round_trip() {
+        let key = Key {
+            day: 9,
+            part: Part::Two,
+            input: 0x1234,
+            build: 0xabcd,
+        };
+        let entry = Entry {
+            answer: "-3".to_string(),
+            elapsed: Duration::from_micros(1500),
+        };
+
+        let line = format_entry(&key, &entry);
+        assert_eq!(
+            "9\t2\t0000000000001234\t000000000000abcd\t1500000\t-3\n",
+            line
+        );
+        assert_eq!(Some((key, entry)), parse_entry(line.trim_end()));
+    }
+
+    #[test]
+    fn test_insert_replaces_older_builds() {
+        let mut cache = Cache::default();
+        let key = Key {
+            day: 1,
+            part: Part::One,
+            input: 1,
+            build: 1,
+        };
+        let entry = Entry {
+            answer: "54".to_string(),
+            elapsed: Duration::ZERO,
+        };
+
+        cache.insert(key, entry.clone());
+        cache.insert(Key { build: 2, ..key }, entry.clone());
+        cache.insert(Key { input: 2, ..key }, entry.clone());
+
+        assert_eq!(2, cache.entries.len());
+        assert!(cache.get(&key).is_none());
+        assert_eq!(Some(&entry), cache.get(&Key { build: 2, ..key }));
+    }
+}
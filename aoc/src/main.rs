@@ -0,0 +1,120 @@
+mod cache;
+mod new;
+mod readme;
+mod registry;
+mod run;
+mod watch;
+
+use anyhow::{anyhow, Result};
+use clap::{value_parser, Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use log::LevelFilter;
+use registry::Day;
+use std::time::Duration;
+
+fn main() -> Result<()> {
+    let matches = Command::new("aoc")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("new")
+                .about("scaffold a new day crate and register it")
+                .arg(
+                    Arg::new("day")
+                        .required(true)
+                        .value_parser(value_parser!(u8))
+                        .help("day of the puzzle, 1 through 25"),
+                ),
+        )
+        .subcommand(
+            Command::new("run")
+                .about("solve registered days and summarize the results")
+                .arg(all_arg("solve every registered day"))
+                .arg(day_arg("day to solve"))
+                .arg(timeout_arg())
+                .arg(no_cache_arg())
+                .group(ArgGroup::new("days").args(["all", "day"]).required(true)),
+        )
+        .subcommand(
+            Command::new("check")
+                .about("solve registered days and report answers differing from the known ones")
+                .arg(all_arg("check every registered day"))
+                .arg(day_arg("day to check"))
+                .arg(timeout_arg())
+                .arg(no_cache_arg())
+                .group(ArgGroup::new("days").args(["all", "day"]).required(true)),
+        )
+        .subcommand(
+            Command::new("readme")
+                .about("solve every registered day and update the results table in the README")
+                .arg(timeout_arg())
+                .arg(no_cache_arg()),
+        )
+        .subcommand(
+            Command::new("watch")
+                .about("re-solve a day whenever its source or input changes")
+                .arg(day_arg("day to watch").required(true))
+                .arg(timeout_arg())
+                .arg(no_cache_arg()),
+        )
+        .get_matches();
+
+    util::init_logger(LevelFilter::Info)?;
+
+    match matches.subcommand() {
+        Some(("new", args)) => new::new_day(*args.get_one::<u8>("day").unwrap()),
+        Some(("run", args)) => run::run(&days(args)?, timeout(args)?, use_cache(args)),
+        Some(("check", args)) => run::check(&days(args)?, timeout(args)?, use_cache(args)),
+        Some(("readme", args)) => readme::readme(registry::DAYS, timeout(args)?, use_cache(args)),
+        Some(("watch", args)) => watch::watch(days(args)?[0], timeout(args)?, use_cache(args)),
+        _ => unreachable!("subcommand is required"),
+    }
+}
+
+fn days(args: &ArgMatches) -> Result<Vec<Day>> {
+    match args.get_one::<u8>("day") {
+        Some(day) => Ok(vec![
+            registry::find(*day).ok_or_else(|| anyhow!("Day {} is not registered", day))?
+        ]),
+        None => Ok(registry::DAYS.to_vec()),
+    }
+}
+
+fn all_arg(help: &'static str) -> Arg {
+    Arg::new("all")
+        .short('a')
+        .long("all")
+        .action(ArgAction::SetTrue)
+        .help(help)
+}
+
+fn day_arg(help: &'static str) -> Arg {
+    Arg::new("day")
+        .short('d')
+        .long("day")
+        .value_parser(value_parser!(u8))
+        .help(help)
+}
+
+fn timeout_arg() -> Arg {
+    Arg::new("timeout")
+        .short('t')
+        .long("timeout")
+        .default_value("15")
+        .value_parser(value_parser!(f64))
+        .help("seconds each solver may run before it is stopped")
+}
+
+fn timeout(args: &ArgMatches) -> Result<Duration> {
+    let seconds = *args.get_one::<f64>("timeout").unwrap();
+    Duration::try_from_secs_f64(seconds).map_err(|e| anyhow!("Invalid timeout {}: {}", seconds, e))
+}
+
+fn no_cache_arg() -> Arg {
+    Arg::new("no-cache")
+        .long("no-cache")
+        .action(ArgAction::SetTrue)
+        .help("solve again even if a cached answer exists")
+}
+
+fn use_cache(args: &ArgMatches) -> bool {
+    !args.get_flag("no-cache")
+}
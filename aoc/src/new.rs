@@ -0,0 +1,147 @@
+use crate::registry::{self, workspace_root};
+use anyhow::{anyhow, Result};
+use log::info;
+use std::fs;
+use std::path::Path;
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/Cargo.toml.tmpl");
+const MAIN_TEMPLATE: &str = include_str!("../templates/main.rs.tmpl");
+
+const DAYS_DECLARATION: &str = "pub const DAYS: &[Day] = &[";
+
+pub fn new_day(day: u8) -> Result<()> {
+    if !(1..=25).contains(&day) {
+        return Err(anyhow!("Invalid day: {}", day));
+    }
+
+    if registry::find(day).is_some() {
+        return Err(anyhow!("Day {} is already registered", day));
+    }
+
+    let name = registry::day_name(day);
+    let dir = workspace_root().join(&name);
+    if dir.exists() {
+        return Err(anyhow!("{} already exists", dir.display()));
+    }
+
+    // Edit both manifests before writing anything, so that a registry or workspace which can't be
+    // parsed leaves nothing behind
+    let manifest_path = workspace_root().join("Cargo.toml");
+    let original_manifest = fs::read_to_string(&manifest_path)?;
+    let manifest = add_workspace_member(&original_manifest, &name)?;
+
+    let registry_path = registry::registry_path();
+    let registry = add_registry_day(&fs::read_to_string(&registry_path)?, day)?;
+
+    let written = write_crate(&dir, &name)
+        .and_then(|()| Ok(fs::write(&manifest_path, manifest)?))
+        .and_then(|()| Ok(fs::write(&registry_path, registry)?));
+
+    if let Err(e) = written {
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::write(&manifest_path, original_manifest);
+        return Err(e);
+    }
+
+    info!("Created {}", dir.display());
+
+    Ok(())
+}
+
+fn write_crate(dir: &Path, name: &str) -> Result<()> {
+    fs::create_dir_all(dir.join("src"))?;
+    fs::create_dir_all(dir.join("input"))?;
+
+    fs::write(
+        dir.join("Cargo.toml"),
+        CARGO_TEMPLATE.replace("{{name}}", name),
+    )?;
+    fs::write(dir.join("src").join("main.rs"), MAIN_TEMPLATE)?;
+    fs::write(dir.join("input").join("example"), "")?;
+    fs::write(dir.join("input").join("input"), "")?;
+
+    Ok(())
+}
+
+fn add_workspace_member(manifest: &str, name: &str) -> Result<String> {
+    let start = manifest
+        .find("members = [")
+        .map(|i| i + "members = [".len())
+        .ok_or_else(|| anyhow!("No workspace members found"))?;
+    let end = manifest[start..]
+        .find(']')
+        .map(|i| start + i)
+        .ok_or_else(|| anyhow!("Unterminated workspace members"))?;
+
+    let mut members = manifest[start..end]
+        .split(',')
+        .map(|m| m.trim().trim_matches('"'))
+        .filter(|m| !m.is_empty())
+        .collect::<Vec<_>>();
+
+    if members.contains(&name) {
+        return Err(anyhow!("{} is already a workspace member", name));
+    }
+
+    members.push(name);
+    members.sort_unstable();
+
+    let mut updated = manifest[..start].to_string();
+    updated.push('\n');
+    for member in members {
+        updated.push_str(&format!("    \"{}\",\n", member));
+    }
+    updated.push_str(&manifest[end..]);
+
+    Ok(updated)
+}
+
+fn add_registry_day(registry: &str, day: u8) -> Result<String> {
+    let start = registry
+        .find(DAYS_DECLARATION)
+        .ok_or_else(|| anyhow!("No day registry found"))?;
+    let end = registry[start..]
+        .find("\n];")
+        .map(|i| start + i + 1)
+        .ok_or_else(|| anyhow!("Unterminated day registry"))?;
+
+    let mut updated = registry[..end].to_string();
+    updated.push_str(&format!(
+        "    Day::new({}, &[Part::One, Part::Two]),\n",
+        day
+    ));
+    updated.push_str(&registry[end..]);
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_add_workspace_member() -> Result<()> {
+        let manifest = "[workspace]\nmembers = [\n    \"aoc\",\n    \"day01\",\n    \"util\",\n]\n";
+
+        assert_eq!(
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"day01\",\n    \"day02\",\n    \"util\",\n]\n",
+            add_workspace_member(manifest, "day02")?
+        );
+
+        assert!(add_workspace_member(manifest, "day01").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_registry_day() -> Result<()> {
+        let registry = "pub const DAYS: &[Day] = &[\n    Day::new(1, &[Part::Two]),\n];\n";
+
+        assert_eq!(
+            "pub const DAYS: &[Day] = &[\n    Day::new(1, &[Part::Two]),\n    Day::new(2, &[Part::One, Part::Two]),\n];\n",
+            add_registry_day(registry, 2)?
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::registry::{workspace_root, Day};
+use crate::run::{self, Check, Outcome, Status};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+
+const START_MARKER: &str = "<!-- results -->";
+const END_MARKER: &str = "<!-- /results -->";
+
+/// Solves every given day and rewrites the results table in the README, between its markers.
+pub fn readme(days: &[Day], timeout: Duration, use_cache: bool) -> Result<()> {
+    let (outcomes, _) = run::solve_days(days, timeout, use_cache)?;
+
+    let path = workspace_root().join("README.md");
+    let readme = fs::read_to_string(&path)?;
+    fs::write(&path, replace_results(&readme, &results_table(&outcomes)))?;
+
+    println!("Updated {} with {} results", path.display(), outcomes.len());
+    Ok(())
+}
+
+/// Times and checks, but not the answers themselves, which shouldn't be published. Times of
+/// cached answers are from whenever they were solved, so are marked as such.
+fn results_table(outcomes: &[Outcome]) -> String {
+    let mut answers_by_day = HashMap::new();
+
+    let rows = outcomes
+        .iter()
+        .map(|o| {
+            let expected = answers_by_day
+                .entry(o.day.day)
+                .or_insert_with(|| run::expected_answers(&o.day));
+
+            let check = match (&o.status, o.check(expected)) {
+                (Status::Failed(_), _) => "failed",
+                (Status::TimedOut(_), _) => "timeout",
+                (_, Check::Correct) => "ok",
+                (_, Check::Incorrect(_)) => "wrong",
+                (_, Check::Unknown) => "-",
+            };
+
+            [
+                o.day.day.to_string(),
+                o.part.to_string(),
+                if o.cached {
+                    format!("{:.2?} (cached)", o.elapsed)
+                } else {
+                    format!("{:.2?}", o.elapsed)
+                },
+                check.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    // Markdown tables need the separator row to be made of pipes and dashes only
+    run::format_table(["Day", "Part", "Time", "Check"], &rows)
+        .lines()
+        .map(|l| format!("| {} |\n", l))
+        .collect()
+}
+
+/// Replaces whatever is between the markers, appending a results section if there are none.
+fn replace_results(readme: &str, table: &str) -> String {
+    let section = format!("{}\n{}{}", START_MARKER, table, END_MARKER);
+
+    match (readme.find(START_MARKER), readme.find(END_MARKER)) {
+        (Some(start), Some(end)) if start < end => format!(
+            "{}{}{}",
+            &readme[..start],
+            section,
+            &readme[end + END_MARKER.len()..]
+        ),
+        _ => format!("{}\n## Results\n\n{}\n", readme.trim_end(), section),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registry;
+    use util::Part;
+
+    #[test]
+    fn test_replace_results() {
+        let table = "| Day |\n";
+
+        let appended = replace_results("# Advent of Code\n", table);
+        assert_eq!(
+            "# Advent of Code\n## Results\n\n<!-- results -->\n| Day |\n<!-- /results -->\n",
+            appended
+        );
+
+        let replaced = replace_results(&appended, "| Part |\n");
+        assert_eq!(
+            "# Advent of Code\n## Results\n\n<!-- results -->\n| Part |\n<!-- /results -->\n",
+            replaced
+        );
+    }
+
+    #[test]
+    fn test_results_table() {
+        let outcome = |cached| Outcome {
+            day: registry::find(1).unwrap(),
+            part: Part::Two,
+            status: Status::Solved("54".to_string()),
+            elapsed: Duration::from_millis(3),
+            cached,
+        };
+
+        let table = results_table(&[outcome(false), outcome(true)]);
+        let rows = table.lines().skip(2).collect::<Vec<_>>();
+        assert!(rows[0].contains("| 3.00ms "));
+        assert!(!rows[0].contains("cached"));
+        assert!(rows[1].contains("| 3.00ms (cached) |"));
+        assert!(!table.contains("54"));
+    }
+}
@@ -0,0 +1,68 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use util::Part;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day {
+    pub day: u8,
+    pub parts: &'static [Part],
+}
+
+impl Day {
+    const fn new(day: u8, parts: &'static [Part]) -> Self {
+        Self { day, parts }
+    }
+
+    pub fn name(&self) -> String {
+        day_name(self.day)
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        workspace_root().join(self.name())
+    }
+
+    pub fn binary(&self) -> PathBuf {
+        target_dir().join("release").join(self.name())
+    }
+}
+
+pub fn day_name(day: u8) -> String {
+    format!("day{:02}", day)
+}
+
+pub fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("aoc is a workspace member")
+}
+
+pub fn target_dir() -> PathBuf {
+    env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_root().join("target"))
+}
+
+pub fn registry_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("registry.rs")
+}
+
+pub fn find(day: u8) -> Option<Day> {
+    DAYS.iter().find(|d| d.day == day).copied()
+}
+
+// Days written before `util::Solution` ignore `--part` and only solve the part listed here. New
+// entries are appended by `aoc new`.
+pub const DAYS: &[Day] = &[
+    Day::new(1, &[Part::Two]),
+    Day::new(2, &[Part::Two]),
+    Day::new(3, &[Part::Two]),
+    Day::new(4, &[Part::Two]),
+    Day::new(5, &[Part::Two]),
+    Day::new(6, &[Part::Two]),
+    Day::new(7, &[Part::Two]),
+    Day::new(8, &[Part::Two]),
+    Day::new(9, &[Part::Two]),
+    Day::new(10, &[Part::One]),
+];
@@ -0,0 +1,592 @@
+use crate::cache::{self, Cache, Key};
+use crate::registry::{workspace_root, Day};
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, Read};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use util::{Input, Part};
+
+const ANSWERS_PATH: &str = "input/answers";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Solved(String),
+    Failed(String),
+    TimedOut(Duration),
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Solved(answer) => write!(f, "{}", answer),
+            Self::Failed(reason) => write!(f, "failed: {}", reason),
+            Self::TimedOut(timeout) => write!(f, "timed out after {:.2?}", timeout),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Correct,
+    Incorrect(String),
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub day: Day,
+    pub part: Part,
+    pub status: Status,
+    pub elapsed: Duration,
+    pub cached: bool,
+}
+
+impl Outcome {
+    pub fn answer(&self) -> Option<&str> {
+        match &self.status {
+            Status::Solved(answer) => Some(answer),
+            Status::Failed(_) | Status::TimedOut(_) => None,
+        }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        matches!(self.status, Status::Solved(_))
+    }
+
+    pub fn check(&self, expected: &HashMap<Part, String>) -> Check {
+        match (self.answer(), expected.get(&self.part)) {
+            (Some(answer), Some(expected)) if answer == expected => Check::Correct,
+            (_, Some(expected)) => Check::Incorrect(expected.clone()),
+            (_, None) => Check::Unknown,
+        }
+    }
+}
+
+/// Builds the release binaries for the given days.
+pub fn build(days: &[Day]) -> Result<()> {
+    let mut command = Command::new(env!("CARGO"));
+    command
+        .current_dir(workspace_root())
+        .args(["build", "--release", "--bins"]);
+
+    for day in days {
+        command.args(["-p", &day.name()]);
+    }
+
+    let status = command.status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("Build failed: {}", status))
+    }
+}
+
+/// Solves one part of a day by running its binary, so a panicking or runaway solver only fails
+/// itself.
+pub fn solve(day: Day, part: Part, input: Input, timeout: Duration) -> Outcome {
+    let start = Instant::now();
+
+    let mut command = Command::new(day.binary());
+    command
+        .current_dir(day.dir())
+        .args(["--input", &input.to_string(), "--part", &part.to_string()])
+        .env("RUST_BACKTRACE", "0")
+        .env("RUST_LIB_BACKTRACE", "0");
+
+    let status = match output_with_timeout(&mut command, timeout) {
+        Ok(Exit::TimedOut) => Status::TimedOut(timeout),
+        Ok(Exit::PipesHeldOpen(status)) => Status::Failed(format!(
+            "{}, but a process it started held its output open",
+            status
+        )),
+        Ok(Exit::Finished(output)) if output.status.success() => {
+            match parse_result(&String::from_utf8_lossy(&output.stdout)) {
+                Some(answer) => Status::Solved(answer),
+                None => Status::Failed("No result found".to_string()),
+            }
+        }
+        Ok(Exit::Finished(output)) => Status::Failed(parse_failure(
+            &String::from_utf8_lossy(&output.stderr),
+            &output.status.to_string(),
+        )),
+        Err(e) => Status::Failed(e.to_string()),
+    };
+
+    let outcome = Outcome {
+        day,
+        part,
+        status,
+        elapsed: start.elapsed(),
+        cached: false,
+    };
+    debug!("{:?}", outcome);
+
+    outcome
+}
+
+/// Solves one part of a day, reusing the cached answer if neither its input nor its binary have
+/// changed since it was last solved.
+pub fn solve_cached(
+    day: Day,
+    part: Part,
+    input: Input,
+    timeout: Duration,
+    cache: Option<&Mutex<Cache>>,
+) -> Outcome {
+    let Some((cache, key)) = cache.and_then(|c| Some((c, Key::new(day, part, input)?))) else {
+        return solve(day, part, input, timeout);
+    };
+
+    if let Some(entry) = cache.lock().unwrap().get(&key) {
+        debug!("Found cached {:?} for {:?}", entry, key);
+        return Outcome {
+            day,
+            part,
+            status: Status::Solved(entry.answer.clone()),
+            elapsed: entry.elapsed,
+            cached: true,
+        };
+    }
+
+    let outcome = solve(day, part, input, timeout);
+    if let Status::Solved(answer) = &outcome.status {
+        cache.lock().unwrap().insert(
+            key,
+            cache::Entry {
+                answer: answer.clone(),
+                elapsed: outcome.elapsed,
+            },
+        );
+    }
+
+    outcome
+}
+
+/// How a solver's process ended.
+#[derive(Debug)]
+enum Exit {
+    Finished(Output),
+    TimedOut,
+    /// The solver exited, but something it started still held its output open at the deadline.
+    PipesHeldOpen(ExitStatus),
+}
+
+/// Runs the command to completion, or kills it along with everything it started once the timeout
+/// elapses.
+fn output_with_timeout(command: &mut Command, timeout: Duration) -> io::Result<Exit> {
+    // A process group of its own lets anything the solver starts be killed along with it
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain both pipes while waiting so a chatty solver can't block on a full pipe
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            kill_group(&mut child)?;
+            child.wait()?;
+            return Ok(Exit::TimedOut);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    // The solver has exited, but something it started may still be holding the pipes open
+    let collect = |output: Option<Receiver<Vec<u8>>>| match output {
+        Some(output) => output
+            .recv_timeout(
+                deadline
+                    .saturating_duration_since(Instant::now())
+                    .max(POLL_INTERVAL),
+            )
+            .ok(),
+        None => Some(Vec::new()),
+    };
+
+    match (collect(stdout), collect(stderr)) {
+        (Some(stdout), Some(stderr)) => Ok(Exit::Finished(Output {
+            status,
+            stdout,
+            stderr,
+        })),
+        _ => {
+            kill_group(&mut child)?;
+            Ok(Exit::PipesHeldOpen(status))
+        }
+    }
+}
+
+/// Kills the solver's process group, falling back to only the solver itself where there are no
+/// process groups or the group is already gone.
+fn kill_group(child: &mut Child) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        let killed = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", child.id())])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+        if killed.success() {
+            return Ok(());
+        }
+    }
+
+    child.kill()
+}
+
+fn read_in_background(mut reader: impl Read + Send + 'static) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = reader.read_to_end(&mut buf);
+        let _ = sender.send(buf);
+    });
+    receiver
+}
+
+/// Solves every part of every given day concurrently, ordered by day and part.
+pub fn solve_all(days: &[Day], timeout: Duration, cache: Option<&Mutex<Cache>>) -> Vec<Outcome> {
+    let mut outcomes = thread::scope(|s| {
+        let handles = days
+            .iter()
+            .flat_map(|day| day.parts.iter().map(move |part| (*day, *part)))
+            .map(|(day, part)| {
+                s.spawn(move || solve_cached(day, part, Input::Actual, timeout, cache))
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("solver thread panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    outcomes.sort_by_key(|o| (o.day.day, o.part));
+    outcomes
+}
+
+/// Builds and solves every given day, through the cache unless `use_cache` is false, returning
+/// the outcomes and the total time taken.
+pub fn solve_days(
+    days: &[Day],
+    timeout: Duration,
+    use_cache: bool,
+) -> Result<(Vec<Outcome>, Duration)> {
+    build(days)?;
+
+    let cache = use_cache.then(|| Mutex::new(Cache::load()));
+
+    let start = Instant::now();
+    let outcomes = solve_all(days, timeout, cache.as_ref());
+    let elapsed = start.elapsed();
+
+    if let Some(cache) = cache {
+        cache.into_inner().unwrap().save()?;
+    }
+
+    Ok((outcomes, elapsed))
+}
+
+pub fn run(days: &[Day], timeout: Duration, use_cache: bool) -> Result<()> {
+    let (outcomes, elapsed) = solve_days(days, timeout, use_cache)?;
+
+    print_table(&outcomes, elapsed);
+
+    if !outcomes.iter().all(Outcome::succeeded) {
+        Err(anyhow!("Not every solution succeeded"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Solves the given days and only reports the answers that differ from the known ones, or that
+/// couldn't be found at all, failing unless there are none.
+pub fn check(days: &[Day], timeout: Duration, use_cache: bool) -> Result<()> {
+    let (outcomes, _) = solve_days(days, timeout, use_cache)?;
+
+    let mut answers_by_day = HashMap::new();
+    let (mut wrong, mut unsolved) = (0, 0);
+    for outcome in &outcomes {
+        let expected = answers_by_day
+            .entry(outcome.day.day)
+            .or_insert_with(|| expected_answers(&outcome.day));
+
+        match (&outcome.status, outcome.check(expected)) {
+            (Status::Failed(_) | Status::TimedOut(_), _) => {
+                println!(
+                    "Day {} part {}: {}",
+                    outcome.day.day, outcome.part, outcome.status
+                );
+                unsolved += 1;
+            }
+            (Status::Solved(_), Check::Incorrect(expected)) => {
+                println!(
+                    "Day {} part {}: {}, expected {}",
+                    outcome.day.day, outcome.part, outcome.status, expected
+                );
+                wrong += 1;
+            }
+            (Status::Solved(_), Check::Correct | Check::Unknown) => (),
+        }
+    }
+
+    if wrong + unsolved > 0 {
+        Err(anyhow!(
+            "Of {} answers, {} are wrong and {} failed",
+            outcomes.len(),
+            wrong,
+            unsolved
+        ))
+    } else {
+        println!("{} answers checked", outcomes.len());
+        Ok(())
+    }
+}
+
+fn print_table(outcomes: &[Outcome], elapsed: Duration) {
+    let mut answers_by_day = HashMap::new();
+
+    let rows = outcomes
+        .iter()
+        .map(|o| {
+            let expected = answers_by_day
+                .entry(o.day.day)
+                .or_insert_with(|| expected_answers(&o.day));
+
+            let (answer, check) = match &o.status {
+                Status::Solved(answer) => (
+                    answer.clone(),
+                    match o.check(expected) {
+                        Check::Correct => "ok".to_string(),
+                        Check::Incorrect(expected) => format!("expected {}", expected),
+                        Check::Unknown => "-".to_string(),
+                    },
+                ),
+                Status::Failed(reason) => (reason.clone(), "failed".to_string()),
+                Status::TimedOut(_) => (o.status.to_string(), "timeout".to_string()),
+            };
+
+            [
+                o.day.day.to_string(),
+                o.part.to_string(),
+                answer,
+                if o.cached {
+                    format!("{:.2?} (cached)", o.elapsed)
+                } else {
+                    format!("{:.2?}", o.elapsed)
+                },
+                check,
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    print!(
+        "{}",
+        format_table(["Day", "Part", "Answer", "Time", "Check"], &rows)
+    );
+
+    let count = |f: fn(&Status) -> bool| outcomes.iter().filter(|o| f(&o.status)).count();
+    println!(
+        "Total: {:.2?} ({} solved, {} failed, {} timed out)",
+        elapsed,
+        count(|s| matches!(s, Status::Solved(_))),
+        count(|s| matches!(s, Status::Failed(_))),
+        count(|s| matches!(s, Status::TimedOut(_))),
+    );
+}
+
+pub fn format_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = usize::max(*width, cell.len());
+        }
+    }
+
+    let format_row = |row: [&str; N]| {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        format!("{}\n", line.trim_end())
+    };
+
+    let mut table = format_row(header);
+    table.push_str(&format_row(
+        widths.map(|w| "-".repeat(w)).each_ref().map(String::as_str),
+    ));
+    for row in rows {
+        table.push_str(&format_row(row.each_ref().map(String::as_str)));
+    }
+
+    table
+}
+
+/// Reads the known answers for a day, one `<part>: <answer>` per line.
+pub fn expected_answers(day: &Day) -> HashMap<Part, String> {
+    fs::read_to_string(day.dir().join(ANSWERS_PATH))
+        .map(|s| parse_answers(&s))
+        .unwrap_or_default()
+}
+
+fn parse_answers(s: &str) -> HashMap<Part, String> {
+    s.lines()
+        .filter_map(|l| l.split_once(':'))
+        .filter_map(|(part, answer)| Some((part.trim().parse().ok()?, answer.trim().to_string())))
+        .collect()
+}
+
+fn parse_result(stdout: &str) -> Option<String> {
+    stdout
+        .lines()
+        .rev()
+        .find_map(|l| l.split_once("Result: "))
+        .map(|(_, result)| result.trim().to_string())
+}
+
+fn parse_failure(stderr: &str, status: &str) -> String {
+    stderr
+        .lines()
+        .map(str::trim)
+        .rfind(|l| !l.is_empty() && !l.starts_with("note:"))
+        .unwrap_or(status)
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_result() {
+        assert_eq!(
+            Some("54".to_string()),
+            parse_result("[2023-12-01T00:00:00Z INFO  day01] Result: 54\n")
+        );
+        assert_eq!(
+            None,
+            parse_result("[2023-12-01T00:00:00Z DEBUG day01] Start\n")
+        );
+    }
+
+    #[test]
+    fn test_parse_failure() {
+        let stderr = "thread 'main' panicked at src/main.rs:255:29:\nnot yet implemented\nnote: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+
+        assert_eq!(
+            "not yet implemented",
+            parse_failure(stderr, "exit status: 101")
+        );
+        assert_eq!("exit status: 101", parse_failure("", "exit status: 101"));
+    }
+
+    #[test]
+    fn test_output_with_timeout() -> Result<()> {
+        let output = output_with_timeout(
+            Command::new("sh").args(["-c", "echo done"]),
+            Duration::from_secs(10),
+        )?;
+        assert!(matches!(output, Exit::Finished(o) if o.stdout == b"done\n"));
+
+        let start = Instant::now();
+        let output = output_with_timeout(
+            Command::new("sh").args(["-c", "exec sleep 10"]),
+            Duration::from_millis(50),
+        )?;
+        assert!(matches!(output, Exit::TimedOut));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_output_with_timeout_kills_process_group() -> Result<()> {
+        // Killed processes may linger as zombies until something reaps them
+        let stays_running = |pid: &str| {
+            let state = || {
+                fs::read_to_string(format!("/proc/{}/stat", pid))
+                    .ok()
+                    .and_then(|s| s.rsplit_once(") ").map(|(_, rest)| rest.starts_with('Z')))
+                    .is_some_and(|zombie| !zombie)
+            };
+            (0..100).all(|_| {
+                state() && {
+                    thread::sleep(POLL_INTERVAL);
+                    true
+                }
+            })
+        };
+        let pid_file = std::env::temp_dir().join(format!("aoc-timeout-{}", std::process::id()));
+
+        // The shell is still running at the deadline, waiting on its grandchild
+        let start = Instant::now();
+        let output = output_with_timeout(
+            Command::new("sh").args([
+                "-c",
+                &format!("sleep 10 & echo $! > {}; wait", pid_file.display()),
+            ]),
+            Duration::from_millis(200),
+        )?;
+        assert!(matches!(output, Exit::TimedOut));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(!stays_running(fs::read_to_string(&pid_file)?.trim()));
+
+        // The shell exits straight away, leaving its grandchild holding the pipes open
+        let start = Instant::now();
+        let output = output_with_timeout(
+            Command::new("sh").args([
+                "-c",
+                &format!("sleep 10 & echo $! > {}", pid_file.display()),
+            ]),
+            Duration::from_millis(200),
+        )?;
+        assert!(matches!(output, Exit::PipesHeldOpen(status) if status.success()));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(!stays_running(fs::read_to_string(&pid_file)?.trim()));
+
+        fs::remove_file(pid_file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers("1: 54\n2: 281\n");
+
+        assert_eq!(Some(&"54".to_string()), answers.get(&Part::One));
+        assert_eq!(Some(&"281".to_string()), answers.get(&Part::Two));
+    }
+
+    #[test]
+    fn test_format_table() {
+        let rows = [["1".to_string(), "54".to_string()]];
+
+        assert_eq!(
+            "Day | Answer\n--- | ------\n1   | 54\n",
+            format_table(["Day", "Answer"], &rows)
+        );
+    }
+}
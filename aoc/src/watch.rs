@@ -0,0 +1,148 @@
+use crate::cache::Cache;
+use crate::registry::Day;
+use crate::run::{self, Status};
+use anyhow::Result;
+use log::{error, info};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use util::{Input, Part};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const SETTLE_INTERVAL: Duration = Duration::from_millis(200);
+
+type Snapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+/// Rebuilds and re-solves a day whenever its source or input changes.
+pub fn watch(day: Day, timeout: Duration, use_cache: bool) -> Result<()> {
+    let dirs = [day.dir().join("src"), day.dir().join("input")];
+    let cache = use_cache.then(|| Mutex::new(Cache::load()));
+
+    let mut previous = HashMap::new();
+    let mut last = snapshot(&dirs);
+
+    loop {
+        solve(day, timeout, cache.as_ref(), &mut previous);
+
+        if let Some(cache) = &cache {
+            cache.lock().unwrap().save()?;
+        }
+
+        info!("Watching {} for changes", day.name());
+        last = wait_for_change(&dirs, last);
+    }
+}
+
+fn solve(
+    day: Day,
+    timeout: Duration,
+    cache: Option<&Mutex<Cache>>,
+    previous: &mut HashMap<(Input, Part), Status>,
+) {
+    if let Err(e) = run::build(&[day]) {
+        error!("{}", e);
+        return;
+    }
+
+    for input in [Input::Test, Input::Actual] {
+        for part in day.parts {
+            let status = run::solve_cached(day, *part, input, timeout, cache).status;
+            println!(
+                "{}",
+                describe(input, *part, &status, previous.get(&(input, *part)))
+            );
+            previous.insert((input, *part), status);
+        }
+    }
+}
+
+fn describe(input: Input, part: Part, status: &Status, previous: Option<&Status>) -> String {
+    let description = format!("{} part {}: {}", input, part, status);
+
+    match previous {
+        None => description,
+        Some(previous) if previous == status => format!("{} (unchanged)", description),
+        Some(previous) => format!("{} (was {})", description, previous),
+    }
+}
+
+/// Blocks until the watched files change, then until they settle so that a burst of writes only
+/// triggers one rebuild.
+fn wait_for_change(dirs: &[PathBuf], mut last: Snapshot) -> Snapshot {
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let current = snapshot(dirs);
+        if current != last {
+            last = current;
+            break;
+        }
+    }
+
+    loop {
+        thread::sleep(SETTLE_INTERVAL);
+
+        let current = snapshot(dirs);
+        if current == last {
+            return current;
+        }
+        last = current;
+    }
+}
+
+fn snapshot(dirs: &[PathBuf]) -> Snapshot {
+    let mut snapshot = BTreeMap::new();
+    for dir in dirs {
+        add_to_snapshot(dir, &mut snapshot);
+    }
+    snapshot
+}
+
+fn add_to_snapshot(dir: &Path, snapshot: &mut Snapshot) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.map_while(Result::ok) {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            add_to_snapshot(&entry.path(), snapshot);
+        } else if let Ok(modified) = metadata.modified() {
+            snapshot.insert(entry.path(), (modified, metadata.len()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let solved = Status::Solved("42".to_string());
+
+        assert_eq!(
+            "test part 1: 42",
+            describe(Input::Test, Part::One, &solved, None)
+        );
+        assert_eq!(
+            "actual part 2: 42 (unchanged)",
+            describe(Input::Actual, Part::Two, &solved, Some(&solved))
+        );
+        assert_eq!(
+            "test part 1: 42 (was failed: not yet implemented)",
+            describe(
+                Input::Test,
+                Part::One,
+                &solved,
+                Some(&Status::Failed("not yet implemented".to_string()))
+            )
+        );
+    }
+}
@@ -3,7 +3,7 @@ use log::info;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::OnceLock;
-use util::MinMax;
+use util::MinMaxIterator;
 
 static STRING_TO_DIGIT_MAP: OnceLock<HashMap<String, usize>> = OnceLock::new();
 
@@ -40,44 +40,21 @@ impl FromStr for CalibrationValue {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // 0 = index, 1 = value
-        let mut left_most: Option<(usize, &usize)> = None;
-        let mut right_most: Option<(usize, &usize)> = None;
+        // The first and last digit, by where they're found in the string
+        let digits = string_to_digit_map()
+            .iter()
+            .flat_map(|(n, v)| s.match_indices(n.as_str()).map(move |(i, _)| (i, *v)))
+            .minmax_by_key(|(i, _)| *i)
+            .ok_or_else(|| anyhow!("Digits not found in {}", s))?;
 
-        for (n, v) in string_to_digit_map() {
-            let min_max = s.match_indices(n).map(|m| m.0).collect::<MinMax>();
-
-            if let Some(min) = min_max.min {
-                if left_most.is_none() || min < left_most.unwrap().0 {
-                    left_most = Some((min, v));
-                }
-            }
-
-            if let Some(max) = min_max.max {
-                if right_most.is_none() || max > right_most.unwrap().0 {
-                    right_most = Some((max, v));
-                }
-            }
-        }
-
-        if left_most.is_none() || right_most.is_none() {
-            return Err(anyhow!(
-                "Digits not found in {} {:?} + {:?}",
-                s,
-                left_most,
-                right_most
-            ));
-        }
-
-        let mut val = left_most.unwrap().1 * 10;
-        val += right_most.unwrap().1;
+        let val = digits.min.1 * 10 + digits.max.1;
 
         Ok(CalibrationValue(val))
     }
 }
 
 fn main() -> Result<()> {
-    let result: usize = util::input()?
+    let result: usize = util::init()?
         .into_iter()
         .map_while(|s| CalibrationValue::from_str(&s).ok())
         .map(|c| c.0)
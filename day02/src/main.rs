@@ -93,7 +93,7 @@ impl FromStr for Game {
 }
 
 fn main() -> Result<()> {
-    let result: usize = util::input()?
+    let result: usize = util::init()?
         .iter()
         .map_while(|g| Game::from_str(g).ok())
         .map(|g| g.min_set())
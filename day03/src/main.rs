@@ -1,14 +1,9 @@
 use anyhow::{anyhow, Result};
 use log::info;
 use std::collections::HashSet;
+use std::path::Path;
 use std::str::FromStr;
-use util::grid_neighbors;
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-struct Position {
-    x: usize,
-    y: usize,
-}
+use util::{Color, Connectivity, Exporter, Grid, Point, Rgb};
 
 #[derive(Debug)]
 struct Gear {
@@ -29,12 +24,12 @@ impl Gear {
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 struct NumWithPosition {
     value: usize,
-    position: Position,
+    position: Point<usize>,
     length: usize,
 }
 
 impl NumWithPosition {
-    fn intersects(&self, pos: &Position) -> bool {
+    fn intersects(&self, pos: &Point<usize>) -> bool {
         if pos.y == self.position.y {
             for i in 0..self.length {
                 if pos.x == self.position.x + i {
@@ -48,7 +43,7 @@ impl NumWithPosition {
 
 #[derive(Debug, Default)]
 struct EngineSchematic {
-    grid: Vec<Vec<char>>,
+    grid: Grid<char>,
     numbers: Vec<NumWithPosition>,
 }
 
@@ -59,9 +54,9 @@ impl EngineSchematic {
         'num_loop: for num in &self.numbers {
             let pos = num.position;
             for i in 0..num.length {
-                for neighbor in grid_neighbors(&self.grid, pos.x + i, pos.y, true) {
-                    let (x, y) = neighbor.into();
-                    let neighbor = self.grid[y][x];
+                let digit = Point::new(pos.x + i, pos.y);
+                for (_, neighbor) in self.grid.neighbors(digit, Connectivity::Eight) {
+                    let neighbor = self.grid[neighbor];
                     if !neighbor.is_numeric() && neighbor != '.' {
                         part_numbers.push(*num);
                         continue 'num_loop;
@@ -78,24 +73,21 @@ impl EngineSchematic {
 
         let mut gears = Vec::new();
 
-        for (y, line) in self.grid.iter().enumerate() {
-            for (x, c) in line.iter().enumerate() {
-                if *c == '*' {
-                    let mut neighboring_part_nums = HashSet::new();
-                    for neighbor in grid_neighbors(&self.grid, x, y, true) {
-                        let (x, y) = neighbor.into();
-                        for part_num in &part_numbers {
-                            if part_num.intersects(&Position { x, y }) {
-                                neighboring_part_nums.insert(*part_num);
-                                break;
-                            }
+        for (pos, c) in self.grid.iter() {
+            if *c == '*' {
+                let mut neighboring_part_nums = HashSet::new();
+                for (_, neighbor) in self.grid.neighbors(pos, Connectivity::Eight) {
+                    for part_num in &part_numbers {
+                        if part_num.intersects(&neighbor) {
+                            neighboring_part_nums.insert(*part_num);
+                            break;
                         }
                     }
-                    if neighboring_part_nums.len() == 2 {
-                        gears.push(Gear {
-                            part_numbers: neighboring_part_nums,
-                        })
-                    }
+                }
+                if neighboring_part_nums.len() == 2 {
+                    gears.push(Gear {
+                        part_numbers: neighboring_part_nums,
+                    })
                 }
             }
         }
@@ -103,10 +95,29 @@ impl EngineSchematic {
         gears
     }
 
+    /// Writes an image of the schematic with part numbers in green, other numbers in red and
+    /// symbols in yellow.
+    fn export(&self, path: &Path) -> Result<()> {
+        let digits = |numbers: &[NumWithPosition]| {
+            numbers
+                .iter()
+                .flat_map(|n| (0..n.length).map(|i| Point::new(n.position.x + i, n.position.y)))
+                .collect::<Vec<_>>()
+        };
+
+        Exporter::new(&self.grid, |c| match c {
+            '.' => Rgb(0, 0, 0),
+            _ => Color::Yellow.into(),
+        })
+        .overlay(digits(&self.numbers), Color::Red)
+        .overlay(digits(&self.part_numbers()), Color::Green)
+        .save(path)
+    }
+
     fn add_num_with_position(
         &mut self,
         value: &mut String,
-        position: &mut Option<Position>,
+        position: &mut Option<Point<usize>>,
     ) -> Result<()> {
         self.numbers.push(NumWithPosition {
             value: usize::from_str(value)?,
@@ -126,7 +137,10 @@ impl TryFrom<Vec<String>> for EngineSchematic {
     type Error = anyhow::Error;
 
     fn try_from(value: Vec<String>) -> Result<Self, Self::Error> {
-        let mut engine_schematic = EngineSchematic::default();
+        let mut engine_schematic = EngineSchematic {
+            grid: Grid::from_lines(&value)?,
+            ..Default::default()
+        };
 
         for (y, line) in value.into_iter().enumerate() {
             let mut value = String::new();
@@ -135,7 +149,7 @@ impl TryFrom<Vec<String>> for EngineSchematic {
             for (x, c) in line.chars().enumerate() {
                 if c.is_numeric() {
                     if position.is_none() {
-                        position = Some(Position { x, y })
+                        position = Some(Point::new(x, y))
                     }
                     value.push(c);
                 } else if !value.is_empty() {
@@ -143,7 +157,6 @@ impl TryFrom<Vec<String>> for EngineSchematic {
                 }
             }
 
-            engine_schematic.grid.push(line.chars().collect());
             if !value.is_empty() {
                 engine_schematic.add_num_with_position(&mut value, &mut position)?;
             }
@@ -154,11 +167,13 @@ impl TryFrom<Vec<String>> for EngineSchematic {
 }
 
 fn main() -> Result<()> {
-    let result: usize = EngineSchematic::try_from(util::input()?)?
-        .gears()
-        .into_iter()
-        .map(|n| n.ratio())
-        .sum();
+    let schematic = EngineSchematic::try_from(util::init()?)?;
+
+    if let Some(path) = util::export_path("day03") {
+        schematic.export(&path)?;
+    }
+
+    let result: usize = schematic.gears().into_iter().map(|n| n.ratio()).sum();
 
     info!("Result: {}", result);
 
@@ -180,7 +195,7 @@ mod tests {
         assert_eq!(
             NumWithPosition {
                 value: 467,
-                position: Position { x: 0, y: 0 },
+                position: Point::new(0, 0),
                 length: 3,
             },
             schematic.numbers[0]
@@ -189,7 +204,7 @@ mod tests {
         assert_eq!(
             NumWithPosition {
                 value: 114,
-                position: Position { x: 5, y: 0 },
+                position: Point::new(5, 0),
                 length: 3,
             },
             schematic.numbers[1]
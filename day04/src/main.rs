@@ -74,7 +74,7 @@ impl FromStr for Card {
 }
 
 fn main() -> Result<()> {
-    let mut cards = util::input()?
+    let mut cards = util::init()?
         .into_iter()
         .map(|l| Card::from_str(&l).unwrap())
         .collect::<Vec<_>>();
@@ -1,7 +1,8 @@
 use anyhow::{anyhow, Result};
-use log::{info, trace};
+use log::{debug, info, trace};
 use std::collections::HashMap;
 use std::str::FromStr;
+use util::{Interval, IntervalSet};
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 enum Entry {
@@ -33,76 +34,34 @@ impl FromStr for Entry {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Range {
-    start: usize,
-    len: usize,
-}
-
-#[derive(Debug)]
+/// Maps the values in `source` onto the same number of values starting at `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct MappingRange {
-    from: usize,
+    source: Interval<usize>,
     to: usize,
-    len: usize,
 }
 
 impl MappingRange {
-    fn adjust(&self, value: usize) -> usize {
-        if self.from > self.to {
-            value - (self.from - self.to)
-        } else {
-            value + (self.to - self.from)
-        }
+    fn apply(&self, value: usize) -> usize {
+        value - self.source.start + self.to
     }
 
-    fn transform(&self, value: &Range) -> Option<Vec<Range>> {
-        let mut updated_ranges = Vec::new();
+    fn image(&self) -> Interval<usize> {
+        Interval::with_len(self.to, self.source.len())
+    }
 
-        if value.start > self.from + self.len || value.start + value.len < self.from {
-            // The given value does not intersect with this mapping range, nothing is transformed
-            return None;
-        }
+    /// Splits the ranges into the values mapped by this entry, after mapping them, and the values
+    /// it leaves unmapped.
+    fn transform(&self, ranges: &IntervalSet<usize>) -> (IntervalSet<usize>, IntervalSet<usize>) {
+        let source = IntervalSet::from(self.source);
 
-        let value_end = value.start + value.len;
-        let mapping_end = self.from + self.len;
+        let mapped = ranges
+            .intersection(&source)
+            .iter()
+            .map(|r| r.rebase(self.source.start, self.to))
+            .collect();
 
-        if value.start >= self.from && value_end <= mapping_end {
-            // The given value is fully contained within the mapping range, transform everything
-            updated_ranges.push(Range {
-                start: self.adjust(value.start),
-                len: value.len,
-            })
-        } else if value.start >= self.from {
-            // The start of the given value's range intersects with the mapping range
-            // value:            [    ]
-            // mapping_range: [    ]
-            //
-            let intersect_len = mapping_end - value.start;
-            updated_ranges.push(Range {
-                start: self.adjust(value.start + intersect_len),
-                len: intersect_len,
-            });
-            updated_ranges.push(Range {
-                start: value.start + intersect_len,
-                len: value.len - intersect_len,
-            });
-        } else {
-            // The end of the given value's range intersects with the mapping range
-            // value:         [    ]
-            // mapping_range:    [    ]
-            //
-            let intersect_len = self.from - value.start;
-            updated_ranges.push(Range {
-                start: value.start,
-                len: value.len - intersect_len,
-            });
-            updated_ranges.push(Range {
-                start: self.adjust(value.start + intersect_len),
-                len: intersect_len,
-            });
-        }
-
-        Some(updated_ranges)
+        (mapped, ranges.difference(&source))
     }
 }
 
@@ -119,11 +78,17 @@ impl FromStr for MappingRange {
             return Err(anyhow! {"Failed to parse range: {}", s});
         }
 
-        let from = m[1];
-        let to = m[0];
-        let len = m[2];
-
-        Ok(MappingRange { from, to, len })
+        // Both the source and the values it maps to have to fit, or applying it would overflow
+        let (to, start, len) = (m[0], m[1], m[2]);
+        let end = start
+            .checked_add(len)
+            .filter(|_| to.checked_add(len).is_some())
+            .ok_or_else(|| anyhow!("Range overflows: {}", s))?;
+
+        Ok(MappingRange {
+            source: Interval::new(start, end),
+            to,
+        })
     }
 }
 
@@ -135,20 +100,161 @@ struct Mapping {
 }
 
 impl Mapping {
-    fn get(&self, range: &Range) -> Option<Vec<Range>> {
+    /// Offers whatever each entry leaves unmapped to the remaining entries, so that a range
+    /// spanning several entries is mapped by all of them. Only values no entry covers pass through
+    /// unchanged.
+    fn get(&self, ranges: &IntervalSet<usize>) -> IntervalSet<usize> {
+        let mut mapped = IntervalSet::new();
+        let mut unmapped = ranges.clone();
+
         for m in &self.entries {
-            let result = m.transform(range);
-            if result.is_some() {
-                return result;
+            let (m_mapped, m_unmapped) = m.transform(&unmapped);
+            mapped = mapped.union(&m_mapped);
+            unmapped = m_unmapped;
+        }
+
+        mapped.union(&unmapped)
+    }
+}
+
+/// A function over every value below `usize::MAX`, made of pieces that each shift an interval of
+/// values. The pieces are sorted, and their sources cover every value exactly once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Piecewise {
+    pieces: Vec<MappingRange>,
+}
+
+impl Piecewise {
+    fn identity() -> Self {
+        Self {
+            pieces: vec![MappingRange {
+                source: Interval::new(0, usize::MAX),
+                to: 0,
+            }],
+        }
+    }
+
+    fn new(mut pieces: Vec<MappingRange>) -> Self {
+        pieces.sort_by_key(|p| p.source.start);
+
+        // Merge neighbouring pieces which shift by the same amount
+        let mut merged: Vec<MappingRange> = Vec::with_capacity(pieces.len());
+        for piece in pieces {
+            match merged.last_mut() {
+                Some(last)
+                    if last.source.end == piece.source.start && last.image().end == piece.to =>
+                {
+                    last.source.end = piece.source.end;
+                }
+                _ => merged.push(piece),
             }
         }
-        None
+
+        Self { pieces: merged }
+    }
+
+    fn apply(&self, value: usize) -> usize {
+        let i = self.pieces.partition_point(|p| p.source.end <= value);
+        self.pieces.get(i).map_or(value, |p| p.apply(value))
+    }
+
+    fn apply_ranges(&self, ranges: &IntervalSet<usize>) -> IntervalSet<usize> {
+        self.pieces
+            .iter()
+            .flat_map(|p| p.transform(ranges).0.intervals().to_vec())
+            .collect()
+    }
+
+    /// This function followed by `next`, splitting each piece wherever its image crosses pieces
+    /// of `next`.
+    fn then(&self, next: &Self) -> Self {
+        let mut pieces = Vec::new();
+
+        for p in &self.pieces {
+            for q in &next.pieces {
+                if let Some(overlap) = p.image().intersection(&q.source) {
+                    pieces.push(MappingRange {
+                        source: overlap.rebase(p.to, p.source.start),
+                        to: q.apply(overlap.start),
+                    });
+                }
+            }
+        }
+
+        Self::new(pieces)
+    }
+
+    /// The values mapped into `targets`, including every one of several values mapping onto the
+    /// same target.
+    fn preimage(&self, targets: &IntervalSet<usize>) -> IntervalSet<usize> {
+        self.pieces
+            .iter()
+            .flat_map(|p| {
+                let reached = targets.intersection(&IntervalSet::from(p.image()));
+                reached
+                    .iter()
+                    .map(|i| i.rebase(p.to, p.source.start))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// The smallest value any of `sources` maps onto, found by walking up through the pieces'
+    /// images and pulling each back to `sources`. Images overlap wherever several values map
+    /// onto the same one, so the walk goes on until the next image starts beyond the smallest
+    /// found so far.
+    fn min_image_of(&self, sources: &IntervalSet<usize>) -> Option<usize> {
+        let mut by_image = self.pieces.iter().collect::<Vec<_>>();
+        by_image.sort_by_key(|p| p.to);
+
+        let mut min: Option<usize> = None;
+        for piece in by_image {
+            if min.is_some_and(|min| piece.to >= min) {
+                break;
+            }
+
+            let reached = sources.intersection(&IntervalSet::from(piece.source));
+            if let Some(source) = reached.min() {
+                let image = piece.apply(source);
+                min = Some(min.map_or(image, |min| min.min(image)));
+            }
+        }
+
+        min
+    }
+}
+
+impl From<&Mapping> for Piecewise {
+    fn from(value: &Mapping) -> Self {
+        let mut pieces = Vec::new();
+        let mut covered = IntervalSet::new();
+
+        // Earlier entries take precedence, as in `Mapping::get`
+        for m in &value.entries {
+            for source in IntervalSet::from(m.source).difference(&covered).iter() {
+                pieces.push(MappingRange {
+                    source: *source,
+                    to: m.apply(source.start),
+                });
+            }
+            covered.insert(m.source);
+        }
+
+        let everything = IntervalSet::from(Interval::new(0, usize::MAX));
+        for gap in everything.difference(&covered).iter() {
+            pieces.push(MappingRange {
+                source: *gap,
+                to: gap.start,
+            });
+        }
+
+        Self::new(pieces)
     }
 }
 
 #[derive(Debug)]
 struct Almanac {
-    seeds: Vec<Range>,
+    seeds: Vec<Interval<usize>>,
     mappings: HashMap<Entry, Mapping>,
 }
 
@@ -170,45 +276,56 @@ impl Almanac {
         Ok((t[0], t[1]))
     }
 
-    fn map(&self, range: &Range, from: Entry) -> Result<(Entry, Vec<Range>)> {
+    fn map(&self, ranges: &IntervalSet<usize>, from: Entry) -> Result<(Entry, IntervalSet<usize>)> {
         let mapping = self
             .mappings
             .get(&from)
             .ok_or_else(|| anyhow!("Failed to find mapping for {:?}", from))?;
 
-        let result = mapping.get(range).unwrap_or_else(|| vec![*range]);
-        trace!("Mapped {:?} to {:?} with {:?}", range, result, mapping);
+        let result = mapping.get(ranges);
+        trace!("Mapped {:?} to {:?} with {:?}", ranges, result, mapping);
 
         Ok((mapping.to, result))
     }
 
     fn seed_range_to_min_location(&self) -> Result<usize> {
         let mut entry = Entry::Seed;
-        let mut ranges = self.seeds.clone();
+        let mut ranges = self.seeds.iter().copied().collect::<IntervalSet<_>>();
 
-        loop {
-            let mut next_entry = entry;
-            let mut next_ranges = Vec::new();
-
-            for range in ranges.iter() {
-                let mut updated_ranges;
+        while entry != Entry::Location {
+            (entry, ranges) = self.map(&ranges, entry)?;
+        }
 
-                (next_entry, updated_ranges) = self.map(range, entry)?;
+        ranges.min().ok_or_else(|| anyhow!("No seeds"))
+    }
 
-                next_ranges.append(&mut updated_ranges);
-            }
+    /// Composes every mapping from seed to location into a single function.
+    fn seed_to_location(&self) -> Result<Piecewise> {
+        let mut entry = Entry::Seed;
+        let mut function = Piecewise::identity();
 
-            entry = next_entry;
-            ranges = next_ranges;
+        while entry != Entry::Location {
+            let mapping = self
+                .mappings
+                .get(&entry)
+                .ok_or_else(|| anyhow!("Failed to find mapping for {:?}", entry))?;
 
-            if entry == Entry::Location {
-                break;
-            }
+            function = function.then(&Piecewise::from(mapping));
+            entry = mapping.to;
         }
 
-        ranges.sort_by(|x, y| x.start.cmp(&y.start));
+        trace!("Seed to location: {:?}", function);
+        Ok(function)
+    }
 
-        Ok(ranges[0].start)
+    /// Walks up through locations, pulling each back to the seeds mapped onto it, until reaching
+    /// one of the seeds.
+    fn min_location_by_preimage(&self) -> Result<usize> {
+        let seeds = self.seeds.iter().copied().collect();
+
+        self.seed_to_location()?
+            .min_image_of(&seeds)
+            .ok_or_else(|| anyhow!("No seeds"))
     }
 }
 
@@ -237,10 +354,11 @@ impl TryFrom<Vec<String>> for Almanac {
                     .collect::<Vec<_>>();
 
                 for n in (0..seed_ranges.len()).step_by(2) {
-                    seeds.push(Range {
-                        start: seed_ranges[n],
-                        len: seed_ranges[n + 1],
-                    });
+                    let (start, len) = (seed_ranges[n], seed_ranges[n + 1]);
+                    let end = start
+                        .checked_add(len)
+                        .ok_or_else(|| anyhow!("Seed range overflows: {} {}", start, len))?;
+                    seeds.push(Interval::new(start, end));
                 }
 
                 continue;
@@ -281,9 +399,48 @@ impl TryFrom<Vec<String>> for Almanac {
 }
 
 fn main() -> Result<()> {
-    let almanac = Almanac::try_from(util::input()?)?;
+    let almanac = Almanac::try_from(util::init()?)?;
     let result = almanac.seed_range_to_min_location()?;
 
+    // Cross-check against the composed function, both forwards and back from the locations
+    let location = almanac.seed_to_location()?;
+    for seed in &almanac.seeds {
+        debug!(
+            "Seeds {}..{} start at location {}",
+            seed.start,
+            seed.end,
+            location.apply(seed.start)
+        );
+    }
+
+    let seeds = almanac.seeds.iter().copied().collect::<IntervalSet<_>>();
+    let composed = location.apply_ranges(&seeds).min();
+    if composed != Some(result) {
+        return Err(anyhow!(
+            "Minimum location {} disagrees with {:?} from the composed function",
+            result,
+            composed
+        ));
+    }
+
+    let by_preimage = almanac.min_location_by_preimage()?;
+    if by_preimage != result {
+        return Err(anyhow!(
+            "Minimum location {} disagrees with {} found back from the locations",
+            result,
+            by_preimage
+        ));
+    }
+
+    let reaching = location
+        .preimage(&Interval::with_len(result, 1).into())
+        .intersection(&seeds);
+    debug!(
+        "Seeds reaching location {}: {:?}",
+        result,
+        reaching.intervals()
+    );
+
     info!("Result: {}", result);
 
     Ok(())
@@ -296,7 +453,7 @@ mod test {
     #[test]
     fn test_mappings_single_seed() -> Result<()> {
         let mut almanac = Almanac::try_from(util::test_input()?)?;
-        almanac.seeds = vec![Range { start: 82, len: 1 }];
+        almanac.seeds = vec![Interval::with_len(82, 1)];
 
         assert_eq!(46, almanac.seed_range_to_min_location()?);
 
@@ -311,4 +468,190 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_range() -> Result<()> {
+        let range = MappingRange::from_str("50 98 2")?;
+        assert_eq!(Interval::new(98, 100), range.source);
+        assert_eq!(51, range.apply(99));
+
+        let max = usize::MAX;
+        assert!(MappingRange::from_str(&format!("0 {} 2", max - 1)).is_err());
+        assert!(MappingRange::from_str(&format!("{} 0 2", max - 1)).is_err());
+        assert!(MappingRange::from_str(&format!("0 {} 1", max - 1)).is_ok());
+
+        Ok(())
+    }
+
+    fn set(intervals: &[(usize, usize)]) -> IntervalSet<usize> {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn test_transform() -> Result<()> {
+        // Maps 10..15 onto 20..25
+        let mapping = MappingRange::from_str("20 10 5")?;
+
+        assert_eq!(
+            (set(&[]), set(&[(0, 10)])),
+            mapping.transform(&set(&[(0, 10)]))
+        );
+        assert_eq!(
+            (set(&[(21, 23)]), set(&[])),
+            mapping.transform(&set(&[(11, 13)]))
+        );
+        assert_eq!(
+            (set(&[(20, 25)]), set(&[(5, 10), (15, 30)])),
+            mapping.transform(&set(&[(5, 30)]))
+        );
+
+        // Mapping downwards
+        let mapping = MappingRange::from_str("0 10 5")?;
+        assert_eq!(
+            (set(&[(4, 5)]), set(&[(15, 16)])),
+            mapping.transform(&set(&[(14, 16)]))
+        );
+
+        Ok(())
+    }
+
+    fn spanning_almanac(seeds: &str) -> Result<Almanac> {
+        // Maps 5..10 up to 100..105 and 15..20 down to 0..5, leaving gaps around them
+        Almanac::try_from(
+            [seeds, "", "seed-to-location map:", "100 5 5", "0 15 5"]
+                .iter()
+                .map(|l| l.to_string())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_range_spanning_entries() -> Result<()> {
+        let almanac = spanning_almanac("seeds: 0 30")?;
+        let mapping = &almanac.mappings[&Entry::Seed];
+
+        assert_eq!(
+            set(&[(0, 5), (10, 15), (20, 30), (100, 105)]),
+            mapping.get(&set(&[(0, 30)]))
+        );
+        assert_eq!(
+            set(&[(0, 5), (10, 15), (100, 105)]),
+            mapping.get(&set(&[(5, 20)]))
+        );
+        assert_eq!(
+            set(&[(2, 3), (12, 13)]),
+            mapping.get(&set(&[(12, 13), (17, 18)]))
+        );
+
+        // Only the second entry maps anything below 5
+        assert_eq!(0, almanac.seed_range_to_min_location()?);
+        assert_eq!(
+            0,
+            spanning_almanac("seeds: 5 15")?.seed_range_to_min_location()?
+        );
+        assert_eq!(
+            10,
+            spanning_almanac("seeds: 7 8")?.seed_range_to_min_location()?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seed_to_location() -> Result<()> {
+        let almanac = Almanac::try_from(util::test_input()?)?;
+        let location = almanac.seed_to_location()?;
+
+        assert_eq!(82, location.apply(79));
+        assert_eq!(43, location.apply(14));
+        assert_eq!(86, location.apply(55));
+        assert_eq!(35, location.apply(13));
+        assert_eq!(
+            46,
+            location
+                .apply_ranges(&set(&[(79, 93), (55, 68)]))
+                .min()
+                .unwrap()
+        );
+        assert_eq!(46, almanac.min_location_by_preimage()?);
+
+        Ok(())
+    }
+
+    fn swapping_almanac(seeds: &str) -> Result<Almanac> {
+        // Seeds 0..5 and 10..15 swap places, then soils 0..2 and 3..5 swap places
+        Almanac::try_from(
+            [
+                seeds,
+                "",
+                "seed-to-soil map:",
+                "10 0 5",
+                "0 10 5",
+                "",
+                "soil-to-location map:",
+                "3 0 2",
+                "0 3 2",
+            ]
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_piecewise() -> Result<()> {
+        let location = swapping_almanac("seeds: 0 1")?.seed_to_location()?;
+
+        assert_eq!(10, location.apply(0));
+        assert_eq!(7, location.apply(7));
+        assert_eq!(3, location.apply(10));
+        assert_eq!(2, location.apply(12));
+        assert_eq!(0, location.apply(13));
+        assert_eq!(1_000, location.apply(1_000));
+
+        for seed in 0..20 {
+            let target = Interval::with_len(location.apply(seed), 1).into();
+            assert_eq!(set(&[(seed, seed + 1)]), location.preimage(&target));
+        }
+
+        // Seeds 15..20 map onto the same locations as the unmapped seeds 0..5
+        let spanning = spanning_almanac("seeds: 0 1")?.seed_to_location()?;
+        assert_eq!(2, spanning.apply(17));
+        assert_eq!(set(&[(2, 3), (17, 18)]), spanning.preimage(&set(&[(2, 3)])));
+        assert_eq!(
+            set(&[(5, 10), (100, 105)]),
+            spanning.preimage(&set(&[(100, 105)]))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_location_cross_check() -> Result<()> {
+        // The spanning almanac maps several seeds onto the same locations
+        for (almanac, expected) in [
+            (swapping_almanac("seeds: 0 12")?, 3),
+            (swapping_almanac("seeds: 11 3")?, 0),
+            (swapping_almanac("seeds: 10 2")?, 3),
+            (swapping_almanac("seeds: 5 5")?, 5),
+            (swapping_almanac("seeds: 20 5")?, 20),
+            (spanning_almanac("seeds: 5 5")?, 100),
+            (spanning_almanac("seeds: 8 10")?, 0),
+            (spanning_almanac("seeds: 3 4 17 1")?, 2),
+        ] {
+            let seed_ranges = almanac.seeds.iter().copied().collect();
+
+            assert_eq!(expected, almanac.seed_range_to_min_location()?);
+            assert_eq!(expected, almanac.min_location_by_preimage()?);
+            assert_eq!(
+                Some(expected),
+                almanac.seed_to_location()?.apply_ranges(&seed_ranges).min()
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -29,14 +29,13 @@ impl TryFrom<Vec<String>> for Races {
         let mut distances = Vec::new();
 
         for line in value {
-            let target;
-            if line.starts_with("Time:") {
-                target = &mut times;
+            let target = if line.starts_with("Time:") {
+                &mut times
             } else if line.starts_with("Distance:") {
-                target = &mut distances;
+                &mut distances
             } else {
                 return Err(anyhow!("Invalid line: {}", line));
-            }
+            };
 
             let (_, values) = line.split_once(':').unwrap();
             target.push(usize::from_str(&values.replace(' ', ""))?);
@@ -50,7 +49,7 @@ impl TryFrom<Vec<String>> for Races {
             ));
         }
 
-        for (time, distance) in times.into_iter().zip(distances.into_iter()) {
+        for (time, distance) in times.into_iter().zip(distances) {
             races.0.push(Race { time, distance });
         }
 
@@ -59,7 +58,7 @@ impl TryFrom<Vec<String>> for Races {
 }
 
 fn main() -> Result<()> {
-    let result = Races::try_from(util::input()?)?
+    let result = Races::try_from(util::init()?)?
         .0
         .into_iter()
         .map(|r| r.ways_to_break_record())
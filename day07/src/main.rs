@@ -262,7 +262,7 @@ impl Hands {
 }
 
 fn main() -> Result<()> {
-    let mut hands = Hands::try_from(util::input()?)?;
+    let mut hands = Hands::try_from(util::init()?)?;
 
     let result = hands.total_winnings();
 
@@ -1,7 +1,9 @@
 use anyhow::{anyhow, Result};
 use log::{debug, info, trace};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
+use util::cycle::{self, Goals};
+use util::number::{self, CongruenceError};
 
 #[derive(Debug)]
 enum Direction {
@@ -55,27 +57,16 @@ struct Map {
 }
 
 impl Map {
-    fn steps(&self, start: &str) -> Result<usize> {
-        let mut steps = 0;
-
-        let mut curr = start;
-        for direction in self.directions.iter().cycle() {
-            let next = self
-                .nodes
-                .get(curr)
-                .ok_or_else(|| anyhow!("No next node for {curr}"))?
-                .next(direction);
-
-            steps += 1;
-
-            trace!("{} + {:?} --> {}", curr, direction, next);
-            curr = next;
-            if curr.ends_with('Z') {
-                return Ok(steps);
-            }
-        }
+    /// Analyses where a ghost is on a Z node. The ghost's state includes its position in the
+    /// directions, since the same node can be reached at different points in them.
+    fn ghost_goals(&self, start: &str) -> Goals {
+        let history = cycle::detect((start, 0), |(node, i)| {
+            let next = self.nodes[*node].next(&self.directions[*i]);
+            (next.as_str(), (i + 1) % self.directions.len())
+        });
 
-        unreachable!()
+        trace!("{} loops after {:?}", start, history.cycle);
+        history.goals(|(node, _)| node.ends_with('Z'))
     }
 
     fn ghost_steps(&self) -> Result<usize> {
@@ -85,19 +76,71 @@ impl Map {
             .filter(|n| n.ends_with('A'))
             .collect::<Vec<_>>();
 
-        let mut steps = vec![0; start_nodes.len()];
-
         debug!("Starting nodes: {:?}", start_nodes);
 
-        for i in 0..start_nodes.len() {
-            steps[i] = self.steps(start_nodes[i])?;
-            debug!("Found path for {} in {} steps", start_nodes[i], steps[i]);
+        let goals = start_nodes
+            .iter()
+            .map(|start| self.ghost_goals(start))
+            .collect::<Vec<_>>();
+
+        for (start, goals) in start_nodes.iter().zip(&goals) {
+            debug!("Ghost from {} reaches Z nodes at {:?}", start, goals);
+        }
+
+        let first = goals.first().ok_or_else(|| anyhow!("No starting nodes"))?;
+
+        // Until every ghost is in its cycle, check each step where the first ghost is on a Z node
+        let prefix = goals.iter().map(|g| g.cycle.prefix).max().unwrap_or(0);
+        if let Some(steps) = first
+            .steps()
+            .take_while(|s| *s < prefix)
+            .find(|s| *s > 0 && goals.iter().all(|g| g.is_goal_after(*s)))
+        {
+            return Ok(steps);
+        }
+
+        // After that each ghost is on a Z node when the steps are congruent to one of its offsets.
+        // Every system combined so far shares the same modulus, so keeping them in a set merges
+        // those with the same residue, bounding them by the modulus instead of the product of
+        // every ghost's offsets. Ghosts with fewer offsets go first to keep the set small
+        let mut ghosts = goals.iter().collect::<Vec<_>>();
+        ghosts.sort_by_key(|g| g.offsets.len());
+
+        let mut systems = HashSet::from([(0, 1)]);
+        for goals in ghosts {
+            let length = goals.cycle.length as i128;
+            let mut combined = HashSet::new();
+            for system in &systems {
+                for offset in &goals.offsets {
+                    let residue = (goals.cycle.prefix + offset) as i128;
+                    match number::chinese_remainder([*system, (residue, length)]) {
+                        Ok(solution) => {
+                            combined.insert(solution);
+                        }
+                        Err(CongruenceError::NoSolution) => {}
+                        Err(e) => return Err(anyhow!("Failed to combine ghost cycles: {}", e)),
+                    }
+                }
+            }
+            systems = combined;
         }
 
-        steps
+        let prefix = prefix.max(1) as i128;
+        systems
+            .into_iter()
+            .map(|(residue, modulus)| {
+                // The first solution at or after the prefix
+                let behind = (prefix - residue).max(0);
+                let cycles = behind.checked_add(modulus - 1)? / modulus;
+                residue.checked_add(cycles.checked_mul(modulus)?)
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| anyhow!("Steps overflow"))?
             .into_iter()
-            .reduce(util::least_common_multiple)
-            .ok_or_else(|| anyhow!("Failed to find LCM"))
+            .min()
+            .ok_or_else(|| anyhow!("Ghosts are never all on Z nodes at the same time"))?
+            .try_into()
+            .map_err(|_| anyhow!("Steps overflow"))
     }
 }
 
@@ -128,6 +171,18 @@ impl TryFrom<Vec<String>> for Map {
             nodes.insert(id.to_string(), Node::from_str(node)?);
         }
 
+        if directions.is_empty() {
+            return Err(anyhow!("No directions"));
+        }
+
+        for node in nodes.values() {
+            for next in [&node.0, &node.1] {
+                if !nodes.contains_key(next) {
+                    return Err(anyhow!("No next node for {next}"));
+                }
+            }
+        }
+
         let map = Map { directions, nodes };
         trace!("{:?}", map);
         Ok(map)
@@ -143,3 +198,95 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Result<Map> {
+        Map::try_from(lines.iter().map(|l| l.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_ghost_steps() -> Result<()> {
+        let map = parse(&[
+            "LR",
+            "",
+            "11A = (11B, XXX)",
+            "11B = (XXX, 11Z)",
+            "11Z = (11B, XXX)",
+            "22A = (22B, XXX)",
+            "22B = (22C, 22C)",
+            "22C = (22Z, 22Z)",
+            "22Z = (22B, 22B)",
+            "XXX = (XXX, XXX)",
+        ])?;
+
+        assert_eq!(6, map.ghost_steps()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ghost_steps_with_offsets() -> Result<()> {
+        // The first ghost is on 1Z after 1, 4, 7... steps and the second after 2, 4, 6..., so the
+        // LCM of their first arrivals is wrong
+        let map = parse(&[
+            "L",
+            "1A = (1Z, 1Z)",
+            "1Z = (1B, 1B)",
+            "1B = (1C, 1C)",
+            "1C = (1Z, 1Z)",
+            "2A = (2B, 2B)",
+            "2B = (2Z, 2Z)",
+            "2Z = (2C, 2C)",
+            "2C = (2Z, 2Z)",
+        ])?;
+
+        assert_eq!(4, map.ghost_steps()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ghost_steps_many_offsets() -> Result<()> {
+        // Each ghost is on a Z node after every even number of steps, reaching two in its cycle,
+        // so combining every choice of offsets would make 2^30 systems
+        let lines = (0..30)
+            .flat_map(|g| {
+                [
+                    format!("{g}A = ({g}B, {g}B)"),
+                    format!("{g}B = ({g}XZ, {g}XZ)"),
+                    format!("{g}XZ = ({g}C, {g}C)"),
+                    format!("{g}C = ({g}YZ, {g}YZ)"),
+                    format!("{g}YZ = ({g}B, {g}B)"),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let map = Map::try_from([vec!["L".to_string()], lines].concat())?;
+
+        assert_eq!(2, map.ghost_steps()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ghost_steps_never_align() -> Result<()> {
+        // Odd and even steps respectively
+        let map = parse(&[
+            "L",
+            "1A = (1Z, 1Z)",
+            "1Z = (1B, 1B)",
+            "1B = (1Z, 1Z)",
+            "2A = (2B, 2B)",
+            "2B = (2Z, 2Z)",
+            "2Z = (2C, 2C)",
+            "2C = (2Z, 2Z)",
+        ])?;
+
+        assert!(map.ghost_steps().is_err());
+        assert!(parse(&["L", "1A = (1Z, 1Z)"]).is_err());
+
+        Ok(())
+    }
+}
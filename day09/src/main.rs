@@ -1,51 +1,36 @@
-use anyhow::Result;
-use log::{info, log_enabled, trace, Level};
+use anyhow::{anyhow, Context, Result};
+use log::{info, trace};
 use std::str::FromStr;
+use util::polynomial::{Newton, Polynomial, PolynomialError};
 
 #[derive(Debug, Default)]
 struct OasisReadings(Vec<isize>);
 
 impl OasisReadings {
-    fn prev_value(&self) -> isize {
-        let mut history: Vec<Vec<isize>> = vec![self.0.clone()];
-
-        let mut i = 0;
-        loop {
-            let curr = &history[i];
-
-            let differences = (0..(curr.len() - 1))
-                .map(|n| curr[n + 1] - curr[n])
-                .collect();
-
-            history.push(differences);
-
-            if history[i + 1].iter().all(|n| *n == 0) {
-                break;
-            }
-
-            i += 1;
-        }
-
-        if log_enabled!(Level::Trace) {
-            for line in &history {
-                trace!("{:?}", line);
-            }
-        }
-
-        let mut prev = None;
-        for i in (0..(history.len() - 1)).rev() {
-            let next_history = &history[i];
-
-            prev = Some(match prev {
-                Some(prev) => next_history[0] - prev,
-                None => {
-                    let prev_history = &history[i + 1];
-                    next_history[0] - prev_history[0]
-                }
-            })
-        }
-
-        prev.unwrap()
+    fn sequence(&self) -> Result<Newton, PolynomialError> {
+        let values = self.0.iter().map(|v| *v as i128).collect::<Vec<_>>();
+        let sequence = Newton::fit(&values)?;
+        trace!("{:?} fits {:?}", self.0, sequence);
+
+        Ok(sequence)
+    }
+
+    fn prev_value(&self) -> Result<isize, PolynomialError> {
+        self.value_at(-1)
+    }
+
+    fn next_value(&self) -> Result<isize, PolynomialError> {
+        self.value_at(self.0.len() as i128)
+    }
+
+    fn value_at(&self, index: i128) -> Result<isize, PolynomialError> {
+        let value = self.sequence()?.evaluate(index)?;
+        isize::try_from(value).map_err(|_| PolynomialError::Overflow)
+    }
+
+    /// The polynomial in the index of each reading, counting the first as zero.
+    fn polynomial(&self) -> Result<Polynomial, PolynomialError> {
+        self.sequence()?.to_polynomial()
     }
 }
 
@@ -55,8 +40,8 @@ impl FromStr for OasisReadings {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let readings = s
             .split_ascii_whitespace()
-            .map_while(|i| isize::from_str(i).ok())
-            .collect::<Vec<_>>();
+            .map(|i| isize::from_str(i).map_err(|_| anyhow!("Invalid reading: {}", i)))
+            .collect::<Result<Vec<_>>>()?;
 
         trace!("{:?}", readings);
         Ok(OasisReadings(readings))
@@ -64,11 +49,30 @@ impl FromStr for OasisReadings {
 }
 
 fn main() -> Result<()> {
-    let result = util::init()?
-        .into_iter()
-        .map_while(|l| OasisReadings::from_str(&l).ok())
-        .map(|r| r.prev_value())
-        .sum::<isize>();
+    let readings = util::init()?
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| OasisReadings::from_str(l).with_context(|| format!("Line: {}", l)))
+        .collect::<Result<Vec<_>>>()?;
+
+    for r in &readings {
+        let polynomial = r
+            .polynomial()
+            .with_context(|| format!("Readings: {:?}", r.0))?;
+        info!(
+            "{:?}: degree {}, f(x) = {}, previous {}, next {}",
+            r.0,
+            polynomial.degree(),
+            polynomial,
+            r.prev_value()?,
+            r.next_value()?
+        );
+    }
+
+    let result = readings.iter().try_fold(0isize, |sum, r| {
+        sum.checked_add(r.prev_value()?)
+            .ok_or(PolynomialError::Overflow)
+    })?;
 
     info!("Result: {result}");
 
@@ -84,13 +88,57 @@ mod test {
         util::init_test_logger()?;
 
         let readings = OasisReadings::from_str("0 3 6 9 12 15")?;
-        assert_eq!(-3, readings.prev_value());
+        assert_eq!(-3, readings.prev_value()?);
+
+        let readings = OasisReadings::from_str("1 3 6 10 15 21")?;
+        assert_eq!(0, readings.prev_value()?);
+
+        let readings = OasisReadings::from_str("10 13 16 21 30 45")?;
+        assert_eq!(5, readings.prev_value()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_polynomial() -> Result<()> {
+        util::init_test_logger()?;
 
         let readings = OasisReadings::from_str("1 3 6 10 15 21")?;
-        assert_eq!(0, readings.prev_value());
+        let polynomial = readings.polynomial()?;
+        assert_eq!(2, polynomial.degree());
+        assert_eq!("(1/2)x^2 + (3/2)x + 1", polynomial.to_string());
+        assert_eq!(28, readings.next_value()?);
 
         let readings = OasisReadings::from_str("10 13 16 21 30 45")?;
-        assert_eq!(5, readings.prev_value());
+        assert_eq!(
+            "(1/3)x^3 - x^2 + (11/3)x + 10",
+            readings.polynomial()?.to_string()
+        );
+        assert_eq!(68, readings.next_value()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() -> Result<()> {
+        util::init_test_logger()?;
+
+        let error = |s: &str| OasisReadings::from_str(s).map(|r| r.prev_value());
+
+        assert!(error("1 x 3").is_err());
+        assert_eq!(Err(PolynomialError::Empty), error("")?);
+        assert_eq!(Err(PolynomialError::NotConverging), error("5")?);
+        assert_eq!(Err(PolynomialError::NotConverging), error("1 2 4 8 16")?);
+        // Each reading fits, but the one before them doesn't
+        assert_eq!(
+            Err(PolynomialError::Overflow),
+            error(&format!(
+                "{} {} {}",
+                isize::MIN,
+                isize::MIN + 1,
+                isize::MIN + 2
+            ))?
+        );
 
         Ok(())
     }
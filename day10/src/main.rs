@@ -1,7 +1,9 @@
 use anyhow::{anyhow, Result};
 use log::{debug, info, trace};
 use std::fmt::Debug;
-use util::Neighbor;
+use std::path::Path;
+use util::search::{self, Search};
+use util::{Color, Connectivity, Direction, Exporter, Grid, Point, Renderer, Rgb};
 
 #[derive(PartialEq, Eq, Clone, Copy)]
 enum Pipe {
@@ -15,6 +17,30 @@ enum Pipe {
     Start,
 }
 
+impl Pipe {
+    /// The pipes the start could be, which each connect two directions.
+    const SHAPES: [Pipe; 6] = [
+        Self::Vertical,
+        Self::Horizontal,
+        Self::NorthAndEast,
+        Self::NorthAndWest,
+        Self::SouthAndWest,
+        Self::SouthAndEast,
+    ];
+
+    fn connects(&self, direction: Direction) -> bool {
+        matches!(
+            (self, direction),
+            (Self::Vertical, Direction::Up | Direction::Down)
+                | (Self::Horizontal, Direction::Left | Direction::Right)
+                | (Self::NorthAndEast, Direction::Up | Direction::Right)
+                | (Self::NorthAndWest, Direction::Up | Direction::Left)
+                | (Self::SouthAndWest, Direction::Down | Direction::Left)
+                | (Self::SouthAndEast, Direction::Down | Direction::Right)
+        )
+    }
+}
+
 impl TryFrom<char> for Pipe {
     type Error = anyhow::Error;
 
@@ -64,179 +90,123 @@ impl Debug for Pipe {
 }
 
 struct Map {
-    grid: Vec<Vec<Pipe>>,
-    start: (usize, usize),
+    grid: Grid<Pipe>,
+    start: Point<usize>,
 }
 
 impl Map {
-    fn _dump(&self, highlight: (usize, usize)) {
-        for (y, line) in self.grid.iter().enumerate() {
-            let mut output = String::new();
-            for (x, line) in line.iter().enumerate() {
-                if (x, y) == highlight {
-                    output.push('*');
-                } else {
-                    output.push(char::from(line));
+    /// Draws the map with the loop and its start highlighted.
+    fn render(&self) -> String {
+        Renderer::new(&self.grid)
+            .box_drawing(true)
+            .layer(self.pipe_loop().distances().keys().copied(), Color::Green)
+            .glyph_layer([self.start], Color::Red, 'S')
+            .render()
+    }
+
+    /// Writes an image of the map with the loop, the tiles it encloses and its start highlighted.
+    fn export(&self, path: &Path) -> Result<()> {
+        let pipe_loop = self.pipe_loop();
+
+        Exporter::new(&self.grid, |p| match p {
+            Pipe::Ground => Rgb(0, 0, 0),
+            _ => Rgb(88, 88, 88),
+        })
+        .overlay(pipe_loop.distances().keys().copied(), Color::Green)
+        .overlay(self.enclosed(&pipe_loop), Color::Blue)
+        .overlay([self.start], Color::Red)
+        .save(path)
+    }
+
+    /// Tiles enclosed by the loop, found by counting how often each row crosses it.
+    fn enclosed(&self, pipe_loop: &Search<Point<usize>, usize>) -> Vec<Point<usize>> {
+        let mut enclosed = Vec::new();
+
+        for y in 0..self.grid.height() {
+            let mut inside = false;
+
+            for x in 0..self.grid.width() {
+                let point = Point::new(x, y);
+
+                if pipe_loop.distance(&point).is_some() {
+                    // Only count pipes heading north, so running along a horizontal pipe which
+                    // turns back the way it came crosses the loop twice or not at all. The start
+                    // has already been given its real shape, so pipes merely pointing into it
+                    // don't count.
+                    if self.grid[point].connects(Direction::Up) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    enclosed.push(point);
                 }
             }
-            info!("{}", output);
         }
+
+        enclosed
+    }
+
+    /// The number of steps along the loop to the point farthest from the start.
+    fn farthest_distance(&self) -> Result<usize> {
+        self.pipe_loop()
+            .distances()
+            .values()
+            .max()
+            .copied()
+            .filter(|d| *d > 0)
+            .ok_or_else(|| anyhow!("No loop found from {:?}", self.start))
+    }
+
+    fn pipe_loop(&self) -> Search<Point<usize>, usize> {
+        search::bfs(self.start, |p| self.connected(*p).collect::<Vec<_>>())
     }
 
-    fn cycle_len(&self) -> usize {
-        let mut len = 0;
+    /// Whether following the pipes from `start` leads back round to it.
+    fn closes_loop(grid: &Grid<Pipe>, start: Point<usize>) -> bool {
+        let Some(mut heading) = Direction::ORTHOGONAL
+            .into_iter()
+            .find(|d| grid[start].connects(*d))
+        else {
+            return false;
+        };
 
-        let mut curr = self.start;
-        let mut prev = None;
+        // Each pipe only has one way out besides the way in, so the walk can't get stuck in a
+        // loop which doesn't pass through the start
+        let mut position = start;
         loop {
-            let next = self.next(curr, &prev);
-            len += 1;
+            let Some(next) = position.step_within(heading, grid) else {
+                return false;
+            };
 
-            if next == self.start {
-                return 1 + len / 2;
+            let came_from = heading.opposite();
+            if !grid[next].connects(came_from) {
+                return false;
+            }
+            if next == start {
+                return true;
             }
 
-            prev = Some(curr);
-            curr = next;
+            let Some(next_heading) = Direction::ORTHOGONAL
+                .into_iter()
+                .find(|d| *d != came_from && grid[next].connects(*d))
+            else {
+                return false;
+            };
+
+            position = next;
+            heading = next_heading;
         }
     }
 
-    fn next(&self, from: (usize, usize), prev: &Option<(usize, usize)>) -> (usize, usize) {
-        let (x, y) = from;
+    /// The neighbours a pipe connects to, which in turn connect back to it.
+    fn connected(&self, from: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        let pipe = self.grid[from];
 
-        let neighbors = util::grid_neighbors(&self.grid, x, y, false)
-            .into_iter()
-            .filter_map(|n| {
-                let (n_x, n_y) = n.into();
-                let pipe = self.grid[n_y][n_x];
-                if pipe == Pipe::Ground {
-                    None
-                } else if let Some((p_x, p_y)) = prev {
-                    // Don't backtrack
-                    if *p_x == n_x && *p_y == n_y {
-                        None
-                    } else {
-                        Some((pipe, n))
-                    }
-                } else {
-                    Some((pipe, n))
-                }
+        self.grid
+            .neighbors(from, Connectivity::Four)
+            .filter(move |(direction, neighbor)| {
+                pipe.connects(*direction) && self.grid[*neighbor].connects(direction.opposite())
             })
-            .collect::<Vec<_>>();
-
-        let current = self.grid[y][x];
-
-        for (neighbor_pipe, neighbor) in neighbors {
-            match (current, neighbor, neighbor_pipe) {
-                // Moving into and out of the start is always allowed
-                (Pipe::Start, _, _) => return neighbor.into(),
-
-                // Ground pipes were filtered out
-                (Pipe::Ground, _, _) | (_, _, Pipe::Ground) => unreachable!(),
-
-                // Diagonal moves are not valid
-                (
-                    _,
-                    Neighbor::UpperLeft(_, _)
-                    | Neighbor::UpperRight(_, _)
-                    | Neighbor::LowerLeft(_, _)
-                    | Neighbor::LowerRight(_, _),
-                    _,
-                ) => unreachable!(),
-
-                // Move out of a vertical pipe
-                (Pipe::Vertical, Neighbor::Left(_, _) | Neighbor::Right(_, _), _) => continue,
-                (
-                    Pipe::Vertical,
-                    Neighbor::Upper(_, _) | Neighbor::Lower(_, _),
-                    Pipe::Vertical
-                    | Pipe::NorthAndEast
-                    | Pipe::NorthAndWest
-                    | Pipe::SouthAndEast
-                    | Pipe::SouthAndWest
-                    | Pipe::Start,
-                ) => return neighbor.into(),
-
-                // Move out of a horizontal pipe
-                (Pipe::Horizontal, Neighbor::Upper(_, _) | Neighbor::Lower(_, _), _) => continue,
-                (
-                    Pipe::Horizontal,
-                    Neighbor::Left(_, _),
-                    Pipe::Horizontal | Pipe::NorthAndEast | Pipe::SouthAndEast | Pipe::Start,
-                ) => return neighbor.into(),
-                (
-                    Pipe::Horizontal,
-                    Neighbor::Right(_, _),
-                    Pipe::Horizontal | Pipe::NorthAndWest | Pipe::SouthAndWest | Pipe::Start,
-                ) => return neighbor.into(),
-
-                // Move out of a north/east pipe
-                (Pipe::NorthAndEast, Neighbor::Left(_, _) | Neighbor::Lower(_, _), _) => continue,
-                (
-                    Pipe::NorthAndEast,
-                    Neighbor::Upper(_, _),
-                    Pipe::Vertical | Pipe::SouthAndWest | Pipe::SouthAndEast,
-                ) => return neighbor.into(),
-                (
-                    Pipe::NorthAndEast,
-                    Neighbor::Right(_, _),
-                    Pipe::Horizontal | Pipe::NorthAndWest | Pipe::SouthAndWest | Pipe::Start,
-                ) => return neighbor.into(),
-
-                // Move out of a north/west pipe
-                (Pipe::NorthAndWest, Neighbor::Left(_, _) | Neighbor::Upper(_, _), Pipe::Start) => {
-                    return neighbor.into()
-                }
-                (Pipe::NorthAndWest, Neighbor::Right(_, _) | Neighbor::Lower(_, _), _) => continue,
-                (
-                    Pipe::NorthAndWest,
-                    Neighbor::Upper(_, _),
-                    Pipe::Vertical | Pipe::SouthAndWest | Pipe::SouthAndEast,
-                ) => return neighbor.into(),
-                (
-                    Pipe::NorthAndWest,
-                    Neighbor::Left(_, _),
-                    Pipe::Horizontal | Pipe::NorthAndEast | Pipe::SouthAndEast,
-                ) => return neighbor.into(),
-
-                // Move out of a south/east pipe
-                (
-                    Pipe::SouthAndEast,
-                    Neighbor::Right(_, _) | Neighbor::Lower(_, _),
-                    Pipe::Start,
-                ) => return neighbor.into(),
-                (Pipe::SouthAndEast, Neighbor::Left(_, _) | Neighbor::Upper(_, _), _) => continue,
-                (
-                    Pipe::SouthAndEast,
-                    Neighbor::Lower(_, _),
-                    Pipe::Vertical | Pipe::NorthAndWest | Pipe::NorthAndEast,
-                ) => return neighbor.into(),
-                (
-                    Pipe::SouthAndEast,
-                    Neighbor::Right(_, _),
-                    Pipe::Horizontal | Pipe::NorthAndWest | Pipe::SouthAndWest,
-                ) => return neighbor.into(),
-
-                // Move out of a south/west pipe
-                (Pipe::SouthAndWest, Neighbor::Left(_, _) | Neighbor::Lower(_, _), Pipe::Start) => {
-                    return neighbor.into()
-                }
-                (Pipe::SouthAndWest, Neighbor::Right(_, _) | Neighbor::Upper(_, _), _) => continue,
-                (
-                    Pipe::SouthAndWest,
-                    Neighbor::Lower(_, _),
-                    Pipe::Vertical | Pipe::NorthAndEast | Pipe::NorthAndWest,
-                ) => return neighbor.into(),
-                (
-                    Pipe::SouthAndWest,
-                    Neighbor::Left(_, _),
-                    Pipe::Horizontal | Pipe::NorthAndEast | Pipe::SouthAndEast,
-                ) => return neighbor.into(),
-
-                (_, _, _) => todo!("{:?} {:?} {:?}", current, neighbor, neighbor_pipe),
-            }
-        }
-        unreachable!("exhausted")
+            .map(|(_, neighbor)| neighbor)
     }
 }
 
@@ -244,30 +214,37 @@ impl TryFrom<Vec<String>> for Map {
     type Error = anyhow::Error;
 
     fn try_from(value: Vec<String>) -> Result<Self, Self::Error> {
-        let mut grid = Vec::with_capacity(value.len());
-
-        let mut start = None;
-        for (y, line) in value.into_iter().enumerate() {
-            let mut pipes = Vec::with_capacity(line.len());
-
-            for (x, c) in line.chars().enumerate() {
-                let pipe = Pipe::try_from(c)?;
-                if pipe == Pipe::Start {
-                    match start {
-                        Some(_) => return Err(anyhow!("Two start positions found")),
-                        None => start = Some((x, y)),
-                    }
-                }
-                pipes.push(pipe);
-            }
+        let mut grid = Grid::<Pipe>::try_from(value)?;
+
+        let starts = grid
+            .iter()
+            .filter(|(_, p)| **p == Pipe::Start)
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+
+        let start = match starts[..] {
+            [start] => start,
+            [] => return Err(anyhow!("No start position found")),
+            _ => return Err(anyhow!("Two start positions found")),
+        };
 
-            grid.push(pipes);
+        // Replace the start with the pipe closing the loop, so that pipes which merely point at it
+        // aren't taken to be part of the loop
+        let mut shapes = Vec::new();
+        for shape in Pipe::SHAPES {
+            grid[start] = shape;
+            if Map::closes_loop(&grid, start) {
+                shapes.push(shape);
+            }
         }
 
-        let map = Map {
-            grid,
-            start: start.ok_or_else(|| anyhow!("No start position found"))?,
+        grid[start] = match shapes[..] {
+            [shape] => shape,
+            [] => return Err(anyhow!("No loop passes through the start")),
+            _ => return Err(anyhow!("Several loops pass through the start")),
         };
+
+        let map = Map { grid, start };
         trace!("{:?}", map);
 
         Ok(map)
@@ -277,21 +254,114 @@ impl TryFrom<Vec<String>> for Map {
 impl Debug for Map {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Start: {:?}", self.start)?;
-        for line in self.grid.iter() {
-            let line: String = line.iter().map(char::from).collect();
-            writeln!(f, "{}", line)?;
-        }
-        Ok(())
+        writeln!(f, "{}", self.grid)
     }
 }
 
 fn main() -> Result<()> {
     let map = Map::try_from(util::init()?)?;
     debug!("{:?}", map);
+    debug!("Loop:\n{}", map.render());
 
-    let result = map.cycle_len();
+    if let Some(path) = util::export_path("day10") {
+        map.export(&path)?;
+    }
+
+    let result = map.farthest_distance()?;
 
     info!("Result: {result}");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_farthest_distance() -> Result<()> {
+        util::init_test_logger()?;
+
+        let map = Map::try_from(vec![
+            ".....".to_string(),
+            ".S-7.".to_string(),
+            ".|.|.".to_string(),
+            ".L-J.".to_string(),
+            ".....".to_string(),
+        ])?;
+        assert_eq!(4, map.farthest_distance()?);
+
+        let map = Map::try_from(vec![
+            "7-F7-".to_string(),
+            ".FJ|7".to_string(),
+            "SJLL7".to_string(),
+            "|F--J".to_string(),
+            "LJ.LJ".to_string(),
+        ])?;
+        assert_eq!(8, map.farthest_distance()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stray_pipe_into_start() -> Result<()> {
+        util::init_test_logger()?;
+
+        // The run of pipes to the left points into the start but is longer than half the loop
+        let map = Map::try_from(vec![
+            "..........".to_string(),
+            "------S-7.".to_string(),
+            "......|.|.".to_string(),
+            "......L-J.".to_string(),
+        ])?;
+        assert_eq!(Pipe::SouthAndEast, map.grid[map.start]);
+        assert_eq!(8, map.pipe_loop().distances().len());
+        assert_eq!(4, map.farthest_distance()?);
+
+        assert!(Map::try_from(vec!["-S-".to_string()]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_enclosed() -> Result<()> {
+        util::init_test_logger()?;
+
+        let map = Map::try_from(vec![
+            "..........".to_string(),
+            ".S------7.".to_string(),
+            ".|F----7|.".to_string(),
+            ".||....||.".to_string(),
+            ".||....||.".to_string(),
+            ".|L-7F-J|.".to_string(),
+            ".|..||..|.".to_string(),
+            ".L--JL--J.".to_string(),
+            "..........".to_string(),
+        ])?;
+        let enclosed = map.enclosed(&map.pipe_loop());
+
+        assert_eq!(4, enclosed.len());
+        assert!(enclosed.contains(&Point::new(2, 6)));
+        assert!(!enclosed.contains(&Point::new(4, 3)));
+
+        // Stray pipes pointing into the start from above and the left are neither part of the
+        // loop nor a crossing of it
+        let map = Map::try_from(vec![
+            "...|......".to_string(),
+            "---S----7.".to_string(),
+            "...|....|.".to_string(),
+            "...L----J.".to_string(),
+        ])?;
+        let pipe_loop = map.pipe_loop();
+        let enclosed = map.enclosed(&pipe_loop);
+
+        assert_eq!(None, pipe_loop.distance(&Point::new(3, 0)));
+        assert_eq!(None, pipe_loop.distance(&Point::new(2, 1)));
+        assert_eq!(
+            (4..8).map(|x| Point::new(x, 2)).collect::<Vec<_>>(),
+            enclosed
+        );
+
+        Ok(())
+    }
+}
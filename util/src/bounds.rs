@@ -0,0 +1,200 @@
+use crate::Point;
+use std::ops::{Add, Sub};
+
+/// The smallest and largest of some values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinMax<T> {
+    pub min: T,
+    pub max: T,
+}
+
+impl<T: Ord + Copy> MinMax<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            min: value,
+            max: value,
+        }
+    }
+
+    /// Finds both in a single pass, or `None` if there are no values.
+    pub fn of(values: impl IntoIterator<Item = T>) -> Option<Self> {
+        values.into_iter().minmax()
+    }
+
+    pub fn include(&mut self, value: T) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.min <= value && value <= self.max
+    }
+}
+
+/// Single-pass minimum and maximum for any iterator.
+pub trait MinMaxIterator: Iterator + Sized {
+    /// The smallest and largest items, in a single pass.
+    fn minmax(self) -> Option<MinMax<Self::Item>>
+    where
+        Self::Item: Ord + Copy,
+    {
+        self.minmax_by_key(|item| *item)
+    }
+
+    /// The items with the smallest and largest keys, in a single pass. Like [`Iterator::min_by_key`]
+    /// and [`Iterator::max_by_key`], ties go to the first minimum and the last maximum.
+    fn minmax_by_key<K: Ord>(
+        mut self,
+        mut key: impl FnMut(&Self::Item) -> K,
+    ) -> Option<MinMax<Self::Item>>
+    where
+        Self::Item: Clone,
+    {
+        let first = self.next()?;
+        let first_key = key(&first);
+
+        let ((min, _), (max, _)) = self.fold(
+            ((first.clone(), key(&first)), (first, first_key)),
+            |(min, max), item| {
+                let k = key(&item);
+                if k < min.1 {
+                    ((item, k), max)
+                } else if k >= max.1 {
+                    (min, (item, k))
+                } else {
+                    (min, max)
+                }
+            },
+        );
+
+        Some(MinMax { min, max })
+    }
+}
+
+impl<I: Iterator> MinMaxIterator for I {}
+
+/// The inclusive corners of the smallest rectangle containing some points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox<T> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Ord + Copy> BoundingBox<T> {
+    pub fn new(point: Point<T>) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// The box around every point, or `None` if there are none.
+    pub fn of(points: impl IntoIterator<Item = Point<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounds = Self::new(points.next()?);
+        points.for_each(|point| bounds.include(point));
+        Some(bounds)
+    }
+
+    pub fn include(&mut self, point: Point<T>) {
+        self.min = Point::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = *self;
+        union.include(other.min);
+        union.include(other.max);
+        union
+    }
+
+    pub fn contains(&self, point: Point<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn xs(&self) -> MinMax<T> {
+        MinMax {
+            min: self.min.x,
+            max: self.max.x,
+        }
+    }
+
+    pub fn ys(&self) -> MinMax<T> {
+        MinMax {
+            min: self.min.y,
+            max: self.max.y,
+        }
+    }
+}
+
+impl<T> BoundingBox<T>
+where
+    T: Ord + Copy + Add<Output = T> + Sub<Output = T> + From<u8>,
+{
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::from(1)
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::from(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_minmax() {
+        assert_eq!(Some(MinMax { min: -3, max: 8 }), MinMax::of([4, -3, 8, 0]));
+        assert_eq!(None, MinMax::<u8>::of([]));
+        assert_eq!(Some(MinMax { min: 'a', max: 'a' }), "a".chars().minmax());
+
+        let mut range = MinMax::new(5);
+        range.include(2);
+        assert!(range.contains(4));
+        assert!(!range.contains(6));
+    }
+
+    #[test]
+    fn test_minmax_by_key() {
+        let words = ["bb", "a", "cc", "d"];
+
+        assert_eq!(
+            Some(MinMax {
+                min: "a",
+                max: "cc"
+            }),
+            words.into_iter().minmax_by_key(|w| w.len())
+        );
+        assert_eq!(
+            Some(MinMax {
+                min: (0, 'b'),
+                max: (3, 'd')
+            }),
+            words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| (i, w.chars().next().unwrap()))
+                .minmax_by_key(|(i, _)| *i)
+        );
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bounds =
+            BoundingBox::of([Point::new(3, -1), Point::new(-2, 4), Point::new(0, 0)]).unwrap();
+
+        assert_eq!(Point::new(-2, -1), bounds.min);
+        assert_eq!(Point::new(3, 4), bounds.max);
+        assert_eq!(6, bounds.width());
+        assert_eq!(6, bounds.height());
+        assert!(bounds.contains(Point::new(3, 4)));
+        assert!(!bounds.contains(Point::new(4, 0)));
+        assert_eq!(MinMax { min: -1, max: 4 }, bounds.ys());
+
+        let other = BoundingBox::new(Point::new(10, 0));
+        assert_eq!(Point::new(10, 4), bounds.union(&other).max);
+        assert_eq!(None, BoundingBox::<i64>::of([]));
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Where an iterated sequence of states starts repeating. The first `prefix` states are never
+/// seen again, and every state from then on recurs every `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step reaching the same state as after `n` steps.
+    pub fn index(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.length
+        }
+    }
+
+    /// The state after `n` steps, taking at most `prefix + length` of them.
+    pub fn state_after<S>(&self, start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+        (0..self.index(n)).fold(start, |state, _| step(&state))
+    }
+
+    /// Walks the prefix and one lap of the cycle to find where goal states are reached.
+    pub fn goals<S>(
+        &self,
+        start: S,
+        mut step: impl FnMut(&S) -> S,
+        mut is_goal: impl FnMut(&S) -> bool,
+    ) -> Goals {
+        let mut state = start;
+        let mut positions = Vec::new();
+
+        for i in 0..self.prefix + self.length {
+            if i > 0 {
+                state = step(&state);
+            }
+            if is_goal(&state) {
+                positions.push(i);
+            }
+        }
+
+        Goals::new(*self, positions)
+    }
+}
+
+/// The steps at which goal states are reached. Each step in `prefix` is reached once, and each
+/// offset in `offsets` is reached at `cycle.prefix + offset + k * cycle.length` for every `k`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Goals {
+    pub cycle: Cycle,
+    pub prefix: Vec<usize>,
+    pub offsets: Vec<usize>,
+}
+
+impl Goals {
+    fn new(cycle: Cycle, positions: Vec<usize>) -> Self {
+        let (prefix, in_cycle): (Vec<_>, Vec<_>) =
+            positions.into_iter().partition(|i| *i < cycle.prefix);
+        let offsets = in_cycle.into_iter().map(|i| i - cycle.prefix).collect();
+
+        Self {
+            cycle,
+            prefix,
+            offsets,
+        }
+    }
+
+    pub fn is_goal_after(&self, n: usize) -> bool {
+        let index = self.cycle.index(n);
+        if index < self.cycle.prefix {
+            self.prefix.contains(&index)
+        } else {
+            self.offsets.contains(&(index - self.cycle.prefix))
+        }
+    }
+
+    /// Every step reaching a goal in ascending order, which never ends if the cycle has a goal.
+    pub fn steps(&self) -> impl Iterator<Item = usize> + '_ {
+        let Cycle { prefix, length } = self.cycle;
+        let laps = (0..).take_while(move |_| !self.offsets.is_empty());
+
+        self.prefix.iter().copied().chain(laps.flat_map(move |lap| {
+            self.offsets
+                .iter()
+                .map(move |offset| prefix + offset + lap * length)
+        }))
+    }
+}
+
+/// Every state from the start up to the first repeat, in order.
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    pub cycle: Cycle,
+    states: Vec<S>,
+}
+
+impl<S> History<S> {
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    pub fn state_after(&self, n: usize) -> &S {
+        &self.states[self.cycle.index(n)]
+    }
+
+    pub fn goals(&self, mut is_goal: impl FnMut(&S) -> bool) -> Goals {
+        let positions = self
+            .states
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| is_goal(state))
+            .map(|(i, _)| i)
+            .collect();
+
+        Goals::new(self.cycle, positions)
+    }
+}
+
+/// Finds the cycle by remembering every state, which is fastest when states are cheap to hash
+/// and the sequence is short enough to keep in memory.
+pub fn detect<S, F>(start: S, mut step: F) -> History<S>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+
+    let mut state = start;
+    let prefix = loop {
+        match seen.entry(state.clone()) {
+            Entry::Occupied(e) => break *e.get(),
+            Entry::Vacant(e) => {
+                e.insert(states.len());
+            }
+        }
+
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    };
+
+    History {
+        cycle: Cycle {
+            prefix,
+            length: states.len() - prefix,
+        },
+        states,
+    }
+}
+
+/// Floyd's tortoise and hare, which only keeps two states at a time.
+pub fn floyd<S, F>(start: S, mut step: F) -> Cycle
+where
+    S: Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+
+    // The hare is now a multiple of the cycle length ahead, so they meet at the cycle's start
+    let mut prefix = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { prefix, length }
+}
+
+/// Brent's algorithm, which only keeps two states at a time and usually steps less than Floyd's.
+pub fn brent<S, F>(start: S, mut step: F) -> Cycle
+where
+    S: Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = (0..length).fold(start, |state, _| step(&state));
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    Cycle { prefix, length }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2
+    fn step(state: &usize) -> usize {
+        match state {
+            5 => 2,
+            s => s + 1,
+        }
+    }
+
+    #[test]
+    fn test_detectors() {
+        let expected = Cycle {
+            prefix: 2,
+            length: 4,
+        };
+
+        assert_eq!(expected, detect(0, step).cycle);
+        assert_eq!(expected, floyd(0, step));
+        assert_eq!(expected, brent(0, step));
+
+        assert_eq!(
+            Cycle {
+                prefix: 0,
+                length: 1
+            },
+            brent(7, |s| *s)
+        );
+    }
+
+    #[test]
+    fn test_state_after() {
+        let history = detect(0, step);
+
+        assert_eq!(&[0, 1, 2, 3, 4, 5], history.states());
+        assert_eq!(&1, history.state_after(1));
+        assert_eq!(&2, history.state_after(6));
+        assert_eq!(&3, history.state_after(1_000_000_003));
+        assert_eq!(3, history.cycle.state_after(0, step, 1_000_000_003));
+    }
+
+    #[test]
+    fn test_goals() {
+        let goals = detect(0, step).goals(|s| s % 2 == 1);
+
+        assert_eq!(vec![1], goals.prefix);
+        assert_eq!(vec![1, 3], goals.offsets);
+        assert_eq!(
+            vec![1, 3, 5, 7, 9],
+            goals.steps().take(5).collect::<Vec<_>>()
+        );
+        assert!(goals.is_goal_after(1_000_001));
+        assert!(!goals.is_goal_after(1_000_000));
+        assert_eq!(goals, brent(0, step).goals(0, step, |s| s % 2 == 1));
+
+        let goals = detect(0, step).goals(|s| *s == 0);
+        assert_eq!(vec![0], goals.steps().collect::<Vec<_>>());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_detectors_agree(next in vec(0..32usize, 32), start in 0..32usize) {
+            let step = |s: &usize| next[*s];
+            let history = detect(start, step);
+
+            prop_assert_eq!(history.cycle, floyd(start, step));
+            prop_assert_eq!(history.cycle, brent(start, step));
+
+            let mut state = start;
+            for n in 0..100 {
+                prop_assert_eq!(&state, history.state_after(n));
+                state = step(&state);
+            }
+        }
+    }
+}
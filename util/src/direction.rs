@@ -0,0 +1,91 @@
+use crate::Point;
+
+/// One of the eight compass directions on a grid, where up is towards `y = 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// All directions, clockwise from up.
+    pub const ALL: [Direction; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// The orthogonal directions, clockwise from up.
+    pub const ORTHOGONAL: [Direction; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn offset(&self) -> Point<isize> {
+        let (x, y) = match self {
+            Self::Up => (0, -1),
+            Self::UpRight => (1, -1),
+            Self::Right => (1, 0),
+            Self::DownRight => (1, 1),
+            Self::Down => (0, 1),
+            Self::DownLeft => (-1, 1),
+            Self::Left => (-1, 0),
+            Self::UpLeft => (-1, -1),
+        };
+        Point::new(x, y)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        !Self::ORTHOGONAL.contains(self)
+    }
+
+    /// Rotates 90 degrees counter-clockwise.
+    pub fn turn_left(&self) -> Self {
+        self.rotate(6)
+    }
+
+    /// Rotates 90 degrees clockwise.
+    pub fn turn_right(&self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Turns around.
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+
+    fn rotate(&self, eighths: usize) -> Self {
+        Self::ALL[(*self as usize + eighths) % Self::ALL.len()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Direction::Left, Direction::Up.turn_left());
+        assert_eq!(Direction::Right, Direction::Up.turn_right());
+        assert_eq!(Direction::Down, Direction::Up.opposite());
+        assert_eq!(Direction::UpLeft, Direction::UpRight.turn_left());
+        assert_eq!(Direction::DownLeft, Direction::UpRight.opposite());
+
+        for direction in Direction::ALL {
+            assert_eq!(direction, direction.turn_left().turn_right());
+            assert_eq!(direction.opposite(), direction.turn_right().turn_right());
+            assert_eq!(
+                Point::new(0, 0),
+                direction.offset() + direction.opposite().offset()
+            );
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{Color, Grid, Point};
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+impl From<Color> for Rgb {
+    fn from(value: Color) -> Self {
+        match value {
+            Color::Red => Rgb(220, 50, 47),
+            Color::Green => Rgb(133, 153, 0),
+            Color::Yellow => Rgb(181, 137, 0),
+            Color::Blue => Rgb(38, 139, 210),
+            Color::Magenta => Rgb(211, 54, 130),
+            Color::Cyan => Rgb(42, 161, 152),
+            Color::White => Rgb(238, 232, 213),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Ppm => "ppm",
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(Self::Ppm),
+            "png" => Ok(Self::Png),
+            "svg" => Ok(Self::Svg),
+            _ => Err(anyhow!("Unknown image format: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<&Path> for ImageFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Path) -> Result<Self, Self::Error> {
+        value
+            .extension()
+            .and_then(|e| e.to_str())
+            .ok_or_else(|| anyhow!("Unknown image format: {}", value.display()))?
+            .parse()
+    }
+}
+
+struct Overlay {
+    points: HashSet<Point<usize>>,
+    color: Rgb,
+}
+
+/// Writes a grid as an image, colouring each cell and then any overlays, such as paths and
+/// regions, on top. Later overlays are drawn over earlier ones.
+pub struct Exporter<'a, T> {
+    grid: &'a Grid<T>,
+    color: Box<dyn Fn(&T) -> Rgb + 'a>,
+    overlays: Vec<Overlay>,
+    scale: usize,
+}
+
+impl<'a, T> Exporter<'a, T> {
+    pub fn new(grid: &'a Grid<T>, color: impl Fn(&T) -> Rgb + 'a) -> Self {
+        Self {
+            grid,
+            color: Box::new(color),
+            overlays: Vec::new(),
+            scale: 4,
+        }
+    }
+
+    pub fn overlay(
+        mut self,
+        points: impl IntoIterator<Item = Point<usize>>,
+        color: impl Into<Rgb>,
+    ) -> Self {
+        self.overlays.push(Overlay {
+            points: points.into_iter().collect(),
+            color: color.into(),
+        });
+        self
+    }
+
+    /// Sets the width and height of each cell, in pixels for raster formats.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Writes the image in the format matching the path's extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let format = ImageFormat::try_from(path)?;
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer, format)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    pub fn write(&self, writer: impl Write, format: ImageFormat) -> Result<()> {
+        match format {
+            ImageFormat::Ppm => self.write_ppm(writer),
+            ImageFormat::Png => self.write_png(writer),
+            ImageFormat::Svg => self.write_svg(writer),
+        }
+    }
+
+    fn color_at(&self, point: Point<usize>) -> Rgb {
+        self.overlays
+            .iter()
+            .rev()
+            .find(|o| o.points.contains(&point))
+            .map(|o| o.color)
+            .unwrap_or_else(|| (self.color)(&self.grid[point]))
+    }
+
+    fn pixels(&self) -> Vec<u8> {
+        let width = self.grid.width() * self.scale;
+        let height = self.grid.height() * self.scale;
+
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for y in 0..height {
+            for x in 0..width {
+                let Rgb(r, g, b) = self.color_at(Point::new(x / self.scale, y / self.scale));
+                pixels.extend([r, g, b]);
+            }
+        }
+        pixels
+    }
+
+    fn write_ppm(&self, mut writer: impl Write) -> Result<()> {
+        write!(
+            writer,
+            "P6\n{} {}\n255\n",
+            self.grid.width() * self.scale,
+            self.grid.height() * self.scale
+        )?;
+        writer.write_all(&self.pixels())?;
+
+        Ok(())
+    }
+
+    fn write_png(&self, writer: impl Write) -> Result<()> {
+        let mut encoder = png::Encoder::new(
+            writer,
+            (self.grid.width() * self.scale).try_into()?,
+            (self.grid.height() * self.scale).try_into()?,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels())?;
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    fn write_svg(&self, mut writer: impl Write) -> Result<()> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+
+        writeln!(
+            writer,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" width="{}" height="{}" shape-rendering="crispEdges">"#,
+            width,
+            height,
+            width * self.scale,
+            height * self.scale
+        )?;
+
+        for (point, _) in self.grid.iter() {
+            writeln!(
+                writer,
+                r#"<rect x="{}" y="{}" width="1" height="1" fill="{}"/>"#,
+                point.x,
+                point.y,
+                self.color_at(point).hex()
+            )?;
+        }
+
+        writeln!(writer, "</svg>")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn exporter(grid: &Grid<bool>) -> Exporter<'_, bool> {
+        Exporter::new(grid, |c| if *c { Rgb(255, 255, 255) } else { Rgb(0, 0, 0) })
+            .overlay([Point::new(1, 0)], Rgb(255, 0, 0))
+            .scale(1)
+    }
+
+    #[test]
+    fn test_ppm() -> Result<()> {
+        let grid = Grid::new(2, 1, vec![true, false])?;
+
+        let mut output = Vec::new();
+        exporter(&grid).write(&mut output, ImageFormat::Ppm)?;
+
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend([255, 255, 255, 255, 0, 0]);
+        assert_eq!(expected, output);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_png() -> Result<()> {
+        let grid = Grid::new(2, 1, vec![true, false])?;
+
+        let mut output = Vec::new();
+        exporter(&grid)
+            .scale(2)
+            .write(&mut output, ImageFormat::Png)?;
+
+        let mut reader = png::Decoder::new(output.as_slice()).read_info()?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels)?;
+
+        assert_eq!((4, 2), (info.width, info.height));
+        assert_eq!([255, 255, 255], pixels[0..3]);
+        assert_eq!([255, 0, 0], pixels[9..12]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_svg() -> Result<()> {
+        let grid = Grid::new(2, 1, vec![true, false])?;
+
+        let mut output = Vec::new();
+        exporter(&grid).write(&mut output, ImageFormat::Svg)?;
+        let output = String::from_utf8(output)?;
+
+        assert!(output.contains(r##"<rect x="0" y="0" width="1" height="1" fill="#ffffff"/>"##));
+        assert!(output.contains(r##"<rect x="1" y="0" width="1" height="1" fill="#ff0000"/>"##));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            Some(ImageFormat::Png),
+            ImageFormat::try_from(Path::new("loop.png")).ok()
+        );
+        assert!(ImageFormat::try_from(Path::new("loop.gif")).is_err());
+        assert!(ImageFormat::try_from(Path::new("loop")).is_err());
+        assert_eq!(Some(ImageFormat::Svg), "svg".parse().ok());
+        assert_eq!("ppm", ImageFormat::Ppm.extension());
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{BoundingBox, Connectivity, Grid, Point};
+
+/// A connected region of cells and its bounding box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub size: usize,
+    pub bounds: BoundingBox<usize>,
+}
+
+/// Every cell labelled with the index of the region it belongs to, if any.
+#[derive(Debug, Clone)]
+pub struct Components {
+    pub labels: Grid<Option<usize>>,
+    pub regions: Vec<Region>,
+}
+
+impl Components {
+    pub fn region_at(&self, point: Point<usize>) -> Option<&Region> {
+        self.labels
+            .get(point)
+            .copied()
+            .flatten()
+            .map(|label| &self.regions[label])
+    }
+}
+
+/// Marks every cell reachable from `start` through cells satisfying `include`.
+pub fn flood_fill<T>(
+    grid: &Grid<T>,
+    start: Point<usize>,
+    connectivity: Connectivity,
+    include: impl FnMut(&T) -> bool,
+) -> Grid<bool> {
+    let mut filled = Grid::filled(grid.width(), grid.height(), false);
+    fill(grid, [start], connectivity, include, &mut filled);
+    filled
+}
+
+/// Marks every cell reachable from the border of the grid through cells satisfying `include`, for
+/// example everything outside of a loop.
+pub fn fill_from_border<T>(
+    grid: &Grid<T>,
+    connectivity: Connectivity,
+    include: impl FnMut(&T) -> bool,
+) -> Grid<bool> {
+    let (width, height) = (grid.width(), grid.height());
+
+    let border = (0..width)
+        .flat_map(|x| [Point::new(x, 0), Point::new(x, height.saturating_sub(1))])
+        .chain(
+            (0..height).flat_map(|y| [Point::new(0, y), Point::new(width.saturating_sub(1), y)]),
+        );
+
+    let mut filled = Grid::filled(width, height, false);
+    fill(grid, border, connectivity, include, &mut filled);
+    filled
+}
+
+/// Labels each connected region of cells satisfying `include`.
+pub fn components<T>(
+    grid: &Grid<T>,
+    connectivity: Connectivity,
+    mut include: impl FnMut(&T) -> bool,
+) -> Components {
+    let mut labels = Grid::filled(grid.width(), grid.height(), None);
+    let mut regions = Vec::new();
+
+    for (point, cell) in grid.iter() {
+        if labels[point].is_some() || !include(cell) {
+            continue;
+        }
+
+        let label = regions.len();
+        let mut region = Region {
+            size: 0,
+            bounds: BoundingBox::new(point),
+        };
+
+        let mut stack = vec![point];
+        labels[point] = Some(label);
+        while let Some(point) = stack.pop() {
+            region.size += 1;
+            region.bounds.include(point);
+
+            for (_, neighbor) in grid.neighbors(point, connectivity) {
+                if labels[neighbor].is_none() && include(&grid[neighbor]) {
+                    labels[neighbor] = Some(label);
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        regions.push(region);
+    }
+
+    Components { labels, regions }
+}
+
+fn fill<T>(
+    grid: &Grid<T>,
+    starts: impl IntoIterator<Item = Point<usize>>,
+    connectivity: Connectivity,
+    mut include: impl FnMut(&T) -> bool,
+    filled: &mut Grid<bool>,
+) {
+    let mut stack = Vec::new();
+    for start in starts {
+        if grid.get(start).is_some_and(&mut include) && !filled[start] {
+            filled[start] = true;
+            stack.push(start);
+        }
+    }
+
+    while let Some(point) = stack.pop() {
+        for (_, neighbor) in grid.neighbors(point, connectivity) {
+            if !filled[neighbor] && include(&grid[neighbor]) {
+                filled[neighbor] = true;
+                stack.push(neighbor);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn count(filled: &Grid<bool>) -> usize {
+        filled.iter().filter(|(_, f)| **f).count()
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = Grid::<char>::from_lines(["..#", "##.", "..."]).unwrap();
+
+        let filled = flood_fill(&grid, Point::new(0, 0), Connectivity::Four, |c| *c == '.');
+        assert_eq!(2, count(&filled));
+
+        let filled = flood_fill(&grid, Point::new(2, 1), Connectivity::Four, |c| *c == '.');
+        assert_eq!(4, count(&filled));
+
+        let filled = flood_fill(&grid, Point::new(0, 0), Connectivity::Eight, |c| *c == '.');
+        assert_eq!(6, count(&filled));
+        assert!(!filled[Point::new(2, 0)]);
+    }
+
+    #[test]
+    fn test_fill_from_border() {
+        let grid =
+            Grid::<char>::from_lines(["......", ".####.", ".#..#.", ".####.", "......"]).unwrap();
+
+        let outside = fill_from_border(&grid, Connectivity::Four, |c| *c == '.');
+
+        assert_eq!(18, count(&outside));
+        assert!(!outside[Point::new(2, 2)]);
+        assert!(!outside[Point::new(3, 2)]);
+    }
+
+    #[test]
+    fn test_components() {
+        let grid = Grid::<char>::from_lines(["#..#", "#..#", "...#", "##.."]).unwrap();
+
+        let labelled = components(&grid, Connectivity::Four, |c| *c == '#');
+        assert_eq!(3, labelled.regions.len());
+
+        assert_eq!(
+            Some(&Region {
+                size: 2,
+                bounds: BoundingBox {
+                    min: Point::new(0, 0),
+                    max: Point::new(0, 1),
+                },
+            }),
+            labelled.region_at(Point::new(0, 1))
+        );
+        assert_eq!(
+            Some(&Region {
+                size: 3,
+                bounds: BoundingBox {
+                    min: Point::new(3, 0),
+                    max: Point::new(3, 2),
+                },
+            }),
+            labelled.region_at(Point::new(3, 0))
+        );
+        assert_eq!(None, labelled.region_at(Point::new(1, 1)));
+
+        let grid = Grid::<char>::from_lines(["#.", ".#"]).unwrap();
+        assert_eq!(
+            2,
+            components(&grid, Connectivity::Four, |c| *c == '#')
+                .regions
+                .len()
+        );
+        assert_eq!(
+            1,
+            components(&grid, Connectivity::Eight, |c| *c == '#')
+                .regions
+                .len()
+        );
+    }
+}
@@ -0,0 +1,557 @@
+use crate::{BoundingBox, Connectivity, Neighbors, Point};
+use anyhow::{anyhow, Result};
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+/// A rectangular grid stored row-major in a single `Vec`, indexed by `(x, y)` with `(0, 0)` at the
+/// top left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Self> {
+        if width * height != cells.len() {
+            return Err(anyhow!(
+                "{} cells do not fill a {}x{} grid",
+                cells.len(),
+                width,
+                height
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Builds a grid by calling `f` with the position of every cell, row by row.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point<usize>) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+            .map(&mut f)
+            .collect();
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses each line into a row, converting every character into a cell.
+    pub fn from_lines<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Result<Self>
+    where
+        T: TryFrom<char>,
+        T::Error: Into<anyhow::Error>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for line in lines {
+            let line = line.as_ref();
+            let len_before = cells.len();
+
+            for c in line.chars() {
+                cells.push(T::try_from(c).map_err(Into::into)?);
+            }
+
+            let line_width = cells.len() - len_before;
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(anyhow!(
+                        "Line {} has width {}, expected {}: {}",
+                        height,
+                        line_width,
+                        width,
+                        line
+                    ));
+                }
+                Some(_) => (),
+            }
+
+            height += 1;
+        }
+
+        Self::new(width.unwrap_or_default(), height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The extent of the grid, or `None` if it has no cells.
+    pub fn bounds(&self) -> Option<BoundingBox<usize>> {
+        if self.cells.is_empty() {
+            return None;
+        }
+
+        Some(BoundingBox {
+            min: Point::new(0, 0),
+            max: Point::new(self.width - 1, self.height - 1),
+        })
+    }
+
+    pub fn contains(&self, point: Point<usize>) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: Point<usize>) -> Option<&T> {
+        if self.contains(point) {
+            Some(&self.cells[self.offset(point)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: Point<usize>) -> Option<&mut T> {
+        if self.contains(point) {
+            let offset = self.offset(point);
+            Some(&mut self.cells[offset])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Column {} out of bounds", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Iterates over every cell along with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (Point::new(i % self.width, i / self.width), cell))
+    }
+
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<(Point<usize>, &T)> {
+        self.iter().find(|(_, cell)| predicate(cell))
+    }
+
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point<usize>> {
+        self.find(|cell| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    /// Iterates over the neighbours of a point that lie within the grid.
+    pub fn neighbors(&self, point: Point<usize>, connectivity: Connectivity) -> Neighbors {
+        Neighbors::new(self, point, connectivity, false)
+    }
+
+    /// Iterates over the neighbours of a point, wrapping around the edges of the grid.
+    pub fn wrapping_neighbors(&self, point: Point<usize>, connectivity: Connectivity) -> Neighbors {
+        Neighbors::new(self, point, connectivity, true)
+    }
+
+    fn offset(&self, point: Point<usize>) -> usize {
+        point.y * self.width + point.x
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Swaps rows and columns, mirroring the grid along its main diagonal.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |p| {
+            self[Point::new(p.y, p.x)].clone()
+        })
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |p| {
+            self[Point::new(p.y, self.height - 1 - p.x)].clone()
+        })
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |p| {
+            self[Point::new(self.width - 1 - p.y, p.x)].clone()
+        })
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Self::from_fn(self.width, self.height, |p| {
+            self[Point::new(self.width - 1 - p.x, p.y)].clone()
+        })
+    }
+
+    /// Mirrors the grid top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Self::from_fn(self.width, self.height, |p| {
+            self[Point::new(p.x, self.height - 1 - p.y)].clone()
+        })
+    }
+
+    /// Repeats the grid `across` times horizontally and `down` times vertically.
+    pub fn tile(&self, across: usize, down: usize) -> Self {
+        Self::from_fn(self.width * across, self.height * down, |p| {
+            self[Point::new(p.x % self.width, p.y % self.height)].clone()
+        })
+    }
+
+    /// Inserts a row before row `y`, or after the last row if `y` is the height.
+    pub fn insert_row(&mut self, y: usize, row: Vec<T>) -> Result<()> {
+        if y > self.height {
+            return Err(anyhow!("Row {} out of bounds", y));
+        }
+        if row.len() != self.width {
+            return Err(anyhow!(
+                "Row has width {}, expected {}",
+                row.len(),
+                self.width
+            ));
+        }
+
+        let offset = y * self.width;
+        self.cells.splice(offset..offset, row);
+        self.height += 1;
+
+        Ok(())
+    }
+
+    /// Inserts a column before column `x`, or after the last column if `x` is the width.
+    pub fn insert_column(&mut self, x: usize, column: Vec<T>) -> Result<()> {
+        if x > self.width {
+            return Err(anyhow!("Column {} out of bounds", x));
+        }
+        if column.len() != self.height {
+            return Err(anyhow!(
+                "Column has height {}, expected {}",
+                column.len(),
+                self.height
+            ));
+        }
+
+        let mut column = column.into_iter();
+        *self = Self::from_fn(self.width + 1, self.height, |p| match p.x.cmp(&x) {
+            Ordering::Less => self[p].clone(),
+            Ordering::Equal => column.next().unwrap(),
+            Ordering::Greater => self[Point::new(p.x - 1, p.y)].clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Repeats every row matching the predicate so that it appears `factor` times.
+    pub fn expand_rows(&self, factor: usize, mut predicate: impl FnMut(&[T]) -> bool) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        let mut height = 0;
+
+        for row in self.rows() {
+            let times = if predicate(row) { factor } else { 1 };
+            for _ in 0..times {
+                cells.extend_from_slice(row);
+            }
+            height += times;
+        }
+
+        Self {
+            width: self.width,
+            height,
+            cells,
+        }
+    }
+
+    /// Repeats every column matching the predicate so that it appears `factor` times.
+    pub fn expand_columns(&self, factor: usize, mut predicate: impl FnMut(&[T]) -> bool) -> Self {
+        self.transpose()
+            .expand_rows(factor, |column| predicate(column))
+            .transpose()
+    }
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        }
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: Point<usize>) -> &Self::Output {
+        self.get(index)
+            .unwrap_or_else(|| panic!("{:?} out of bounds", index))
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, index: Point<usize>) -> &mut Self::Output {
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("{:?} out of bounds", index))
+    }
+}
+
+impl<T> TryFrom<Vec<String>> for Grid<T>
+where
+    T: TryFrom<char>,
+    T::Error: Into<anyhow::Error>,
+{
+    type Error = anyhow::Error;
+
+    fn try_from(value: Vec<String>) -> Result<Self, Self::Error> {
+        Self::from_lines(value)
+    }
+}
+
+impl<T> Display for Grid<T>
+where
+    for<'a> char: From<&'a T>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            let row: String = row.iter().map(char::from).collect();
+            write!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Cell {
+        Open,
+        Wall,
+    }
+
+    impl TryFrom<char> for Cell {
+        type Error = anyhow::Error;
+
+        fn try_from(value: char) -> Result<Self, Self::Error> {
+            match value {
+                '.' => Ok(Self::Open),
+                '#' => Ok(Self::Wall),
+                _ => Err(anyhow!("Invalid cell: {}", value)),
+            }
+        }
+    }
+
+    impl From<&Cell> for char {
+        fn from(value: &Cell) -> Self {
+            match value {
+                Cell::Open => '.',
+                Cell::Wall => '#',
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_and_display() -> Result<()> {
+        let grid = Grid::<Cell>::from_lines(["..#", "#.."])?;
+
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!(Cell::Wall, grid[Point::new(2, 0)]);
+        assert_eq!(Cell::Wall, grid[Point::new(0, 1)]);
+        assert_eq!("..#\n#..", grid.to_string());
+
+        assert!(Grid::<Cell>::from_lines(["..#", "#."]).is_err());
+        assert!(Grid::<Cell>::from_lines(["..x"]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get() -> Result<()> {
+        let mut grid = Grid::new(2, 2, vec![1, 2, 3, 4])?;
+
+        assert_eq!(Some(&2), grid.get(Point::new(1, 0)));
+        assert_eq!(Some(&3), grid.get(Point::new(0, 1)));
+        assert_eq!(None, grid.get(Point::new(2, 0)));
+        assert_eq!(None, grid.get(Point::new(0, 2)));
+        assert_eq!(Some(Point::new(1, 1)), grid.bounds().map(|b| b.max));
+        assert_eq!(None, Grid::<u8>::default().bounds());
+
+        *grid.get_mut(Point::new(1, 1)).unwrap() = 5;
+        assert_eq!(5, grid[Point::new(1, 1)]);
+
+        assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rows_and_columns() -> Result<()> {
+        let grid = Grid::<char>::from_lines(["abc", "def"])?;
+
+        assert_eq!(
+            vec!["abc", "def"],
+            grid.rows()
+                .map(|r| r.iter().collect::<String>())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["ad", "be", "cf"],
+            grid.columns()
+                .map(|c| c.collect::<String>())
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find() -> Result<()> {
+        let grid = Grid::<char>::from_lines(["ab", "cd"])?;
+
+        assert_eq!(Some((Point::new(0, 1), &'c')), grid.find(|c| *c == 'c'));
+        assert_eq!(Some(Point::new(1, 1)), grid.position(|c| *c == 'd'));
+        assert_eq!(None, grid.position(|c| *c == 'e'));
+
+        Ok(())
+    }
+
+    fn show(grid: &Grid<char>) -> String {
+        grid.rows()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_transformations() -> Result<()> {
+        let grid = Grid::<char>::from_lines(["abc", "def"])?;
+
+        assert_eq!("ad\nbe\ncf", show(&grid.transpose()));
+        assert_eq!("da\neb\nfc", show(&grid.rotate_clockwise()));
+        assert_eq!("cf\nbe\nad", show(&grid.rotate_counter_clockwise()));
+        assert_eq!("cba\nfed", show(&grid.flip_horizontal()));
+        assert_eq!("def\nabc", show(&grid.flip_vertical()));
+        assert_eq!("abcabc\ndefdef\nabcabc\ndefdef", show(&grid.tile(2, 2)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert() -> Result<()> {
+        let mut grid = Grid::<char>::from_lines(["ab", "cd"])?;
+
+        grid.insert_row(1, vec!['x', 'y'])?;
+        assert_eq!("ab\nxy\ncd", show(&grid));
+
+        grid.insert_column(2, vec!['1', '2', '3'])?;
+        assert_eq!("ab1\nxy2\ncd3", show(&grid));
+
+        assert!(grid.insert_row(4, vec!['a', 'b', 'c']).is_err());
+        assert!(grid.insert_row(0, vec!['a']).is_err());
+        assert!(grid.insert_column(0, vec!['a']).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_expand() -> Result<()> {
+        let grid = Grid::<char>::from_lines(["#..", "...", "..#"])?;
+        let empty = |cells: &[char]| cells.iter().all(|c| *c == '.');
+
+        assert_eq!("#..\n...\n...\n..#", show(&grid.expand_rows(2, empty)));
+        assert_eq!("#...\n....\n...#", show(&grid.expand_columns(2, empty)));
+        assert_eq!(
+            "#.\n.#",
+            show(&grid.expand_columns(0, empty).expand_rows(0, empty))
+        );
+
+        Ok(())
+    }
+
+    fn grids() -> impl Strategy<Value = Grid<u8>> {
+        (1..8usize, 1..8usize).prop_flat_map(|(width, height)| {
+            vec(any::<u8>(), width * height)
+                .prop_map(move |cells| Grid::new(width, height, cells).unwrap())
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_four_rotations_are_identity(grid in grids()) {
+            let rotated = (0..4).fold(grid.clone(), |g, _| g.rotate_clockwise());
+            prop_assert_eq!(&grid, &rotated);
+        }
+
+        #[test]
+        fn prop_rotations_are_inverse(grid in grids()) {
+            prop_assert_eq!(&grid, &grid.rotate_clockwise().rotate_counter_clockwise());
+        }
+
+        #[test]
+        fn prop_transpose_is_involution(grid in grids()) {
+            prop_assert_eq!(&grid, &grid.transpose().transpose());
+        }
+
+        #[test]
+        fn prop_flips_are_involutions(grid in grids()) {
+            prop_assert_eq!(&grid, &grid.flip_horizontal().flip_horizontal());
+            prop_assert_eq!(&grid, &grid.flip_vertical().flip_vertical());
+        }
+
+        #[test]
+        fn prop_rotation_is_transposed_flip(grid in grids()) {
+            prop_assert_eq!(grid.rotate_clockwise(), grid.transpose().flip_horizontal());
+            prop_assert_eq!(grid.rotate_counter_clockwise(), grid.transpose().flip_vertical());
+        }
+
+        #[test]
+        fn prop_tile_repeats(grid in grids(), across in 1..4usize, down in 1..4usize) {
+            let tiled = grid.tile(across, down);
+            prop_assert_eq!(grid.width() * across, tiled.width());
+            prop_assert_eq!(grid.height() * down, tiled.height());
+            for (p, cell) in tiled.iter() {
+                prop_assert_eq!(&grid[Point::new(p.x % grid.width(), p.y % grid.height())], cell);
+            }
+        }
+
+        #[test]
+        fn prop_expand_by_one_is_identity(grid in grids()) {
+            prop_assert_eq!(&grid, &grid.expand_rows(1, |_| true).expand_columns(1, |_| true));
+        }
+    }
+}
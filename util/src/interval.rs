@@ -0,0 +1,360 @@
+use std::ops::{Add, Sub};
+
+/// The half-open interval `start..end`, which is empty unless `start < end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Copy + Ord> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether the intervals share any values.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The values in both intervals, or `None` if there are none.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Some(Self::new(
+            self.start.max(other.start),
+            self.end.min(other.end),
+        ))
+        .filter(|i| !i.is_empty())
+    }
+
+    /// A single interval covering both, if they overlap or touch.
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        match (self.is_empty(), other.is_empty()) {
+            (true, _) => Some(*other),
+            (_, true) => Some(*self),
+            _ if self.start <= other.end && other.start <= self.end => Some(Self::new(
+                self.start.min(other.start),
+                self.end.max(other.end),
+            )),
+            _ => None,
+        }
+    }
+
+    /// The values not in `other`, as the non-empty pieces before and after it.
+    pub fn difference(&self, other: &Self) -> (Option<Self>, Option<Self>) {
+        if other.is_empty() {
+            return (Some(*self).filter(|i| !i.is_empty()), None);
+        }
+
+        let before = Self::new(self.start, self.end.min(other.start));
+        let after = Self::new(self.start.max(other.end), self.end);
+        (
+            Some(before).filter(|i| !i.is_empty()),
+            Some(after).filter(|i| !i.is_empty()),
+        )
+    }
+
+    /// Splits into the non-empty pieces below and from `at`.
+    pub fn split_at(&self, at: T) -> (Option<Self>, Option<Self>) {
+        let at = at.clamp(self.start, self.end.max(self.start));
+        (
+            Some(Self::new(self.start, at)).filter(|i| !i.is_empty()),
+            Some(Self::new(at, self.end)).filter(|i| !i.is_empty()),
+        )
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T>> Interval<T> {
+    pub fn with_len(start: T, len: T) -> Self {
+        Self::new(start, start + len)
+    }
+
+    pub fn shift(&self, offset: T) -> Self {
+        Self::new(self.start + offset, self.end + offset)
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Interval<T> {
+    pub fn len(&self) -> T {
+        self.end.max(self.start) - self.start
+    }
+
+    /// Moves the interval so that `from` lands on `to`, without going through negative offsets,
+    /// so that unsigned intervals can move down. The interval must not start before `from`.
+    pub fn rebase(&self, from: T, to: T) -> Self {
+        Self::new(self.start - from + to, self.end - from + to)
+    }
+}
+
+/// A set of values stored as sorted, disjoint intervals, with touching intervals merged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(i).is_some_and(|i| i.contains(value))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // Every interval touching the new one is replaced by their union
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, i| merged.union(i).unwrap());
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        other.iter().for_each(|i| union.insert(*i));
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.iter().peekable(), other.iter().peekable());
+        let mut intervals = Vec::new();
+
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            intervals.extend(x.intersection(y));
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        // Pieces of disjoint, non-touching intervals can't touch each other
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+
+        for interval in self.iter() {
+            let mut remaining = Some(*interval);
+            for removed in other.iter() {
+                let Some(rest) = remaining else {
+                    break;
+                };
+                let (before, after) = rest.difference(removed);
+                intervals.extend(before);
+                remaining = after;
+            }
+            intervals.extend(remaining);
+        }
+
+        Self { intervals }
+    }
+
+    /// Splits into the values below and from `at`.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let (mut below, mut above) = (Vec::new(), Vec::new());
+
+        for interval in self.iter() {
+            let (b, a) = interval.split_at(at);
+            below.extend(b);
+            above.extend(a);
+        }
+
+        (Self { intervals: below }, Self { intervals: above })
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T>> IntervalSet<T> {
+    pub fn shift(&self, offset: T) -> Self {
+        Self {
+            intervals: self.iter().map(|i| i.shift(offset)).collect(),
+        }
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> IntervalSet<T> {
+    /// The number of values in the set.
+    pub fn len(&self) -> T
+    where
+        T: Default,
+    {
+        self.iter().fold(T::default(), |len, i| len + i.len())
+    }
+}
+
+impl<T: Copy + Ord> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|i| set.insert(i));
+        set
+    }
+}
+
+impl<T: Copy + Ord> From<Interval<T>> for IntervalSet<T> {
+    fn from(value: Interval<T>) -> Self {
+        Self::from_iter([value])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::new(2, 6);
+        let b = Interval::with_len(4, 4);
+
+        assert_eq!(4, a.len());
+        assert!(a.contains(2) && !a.contains(6));
+        assert_eq!(Some(Interval::new(4, 6)), a.intersection(&b));
+        assert_eq!(None, a.intersection(&Interval::new(6, 9)));
+        assert_eq!(Some(Interval::new(2, 8)), a.union(&b));
+        assert_eq!(Some(Interval::new(2, 9)), a.union(&Interval::new(6, 9)));
+        assert_eq!(None, a.union(&Interval::new(7, 9)));
+        assert_eq!((Some(Interval::new(2, 4)), None), a.difference(&b));
+        assert_eq!(
+            (Some(Interval::new(2, 3)), Some(Interval::new(4, 6))),
+            a.difference(&Interval::new(3, 4))
+        );
+        assert_eq!((None, None), a.difference(&Interval::new(0, 10)));
+        assert_eq!((None, Some(a)), a.difference(&Interval::new(0, 1)));
+        assert_eq!((Some(a), None), a.difference(&Interval::new(8, 9)));
+        assert_eq!(
+            (Some(Interval::new(2, 3)), Some(Interval::new(3, 6))),
+            a.split_at(3)
+        );
+        assert_eq!((None, Some(a)), a.split_at(0));
+        assert_eq!(Interval::new(5, 9), a.shift(3));
+        assert_eq!(Interval::new(0, 4), a.rebase(2, 0));
+        assert!(Interval::new(3, 3).is_empty());
+        assert_eq!(0, Interval::new(5, 3).len());
+    }
+
+    #[test]
+    fn test_set() {
+        let set = IntervalSet::from_iter([
+            Interval::new(10, 12),
+            Interval::new(0, 3),
+            Interval::new(3, 5),
+            Interval::new(11, 15),
+            Interval::new(7, 7),
+        ]);
+
+        assert_eq!(
+            &[Interval::new(0, 5), Interval::new(10, 15)],
+            set.intervals()
+        );
+        assert_eq!(10, set.len());
+        assert_eq!(Some(0), set.min());
+        assert!(set.contains(4) && !set.contains(5) && set.contains(14));
+
+        let other = IntervalSet::from(Interval::new(4, 11));
+        assert_eq!(
+            &[Interval::new(4, 5), Interval::new(10, 11)],
+            set.intersection(&other).intervals()
+        );
+        assert_eq!(
+            &[Interval::new(0, 4), Interval::new(11, 15)],
+            set.difference(&other).intervals()
+        );
+        assert_eq!(&[Interval::new(0, 15)], set.union(&other).intervals());
+
+        let (below, above) = set.split_at(12);
+        assert_eq!(
+            &[Interval::new(0, 5), Interval::new(10, 12)],
+            below.intervals()
+        );
+        assert_eq!(&[Interval::new(12, 15)], above.intervals());
+        assert_eq!(Some(100), set.shift(100).min());
+    }
+
+    fn sets() -> impl Strategy<Value = IntervalSet<u8>> {
+        vec((0..60u8, 0..8u8), 0..6).prop_map(|intervals| {
+            intervals
+                .into_iter()
+                .map(|(start, len)| Interval::with_len(start, len))
+                .collect()
+        })
+    }
+
+    fn is_normalised(set: &IntervalSet<u8>) -> bool {
+        set.iter().all(|i| !i.is_empty())
+            && set.intervals().windows(2).all(|w| w[0].end < w[1].start)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_set_operations(a in sets(), b in sets()) {
+            let union = a.union(&b);
+            let intersection = a.intersection(&b);
+            let difference = a.difference(&b);
+
+            prop_assert!(is_normalised(&union));
+            prop_assert!(is_normalised(&intersection));
+            prop_assert!(is_normalised(&difference));
+
+            for x in 0..70 {
+                prop_assert_eq!(a.contains(x) || b.contains(x), union.contains(x));
+                prop_assert_eq!(a.contains(x) && b.contains(x), intersection.contains(x));
+                prop_assert_eq!(a.contains(x) && !b.contains(x), difference.contains(x));
+            }
+        }
+
+        #[test]
+        fn prop_split_and_shift(a in sets(), at in 0..70u8, offset in 0..100u8) {
+            let (below, above) = a.split_at(at);
+            prop_assert_eq!(&a, &below.union(&above));
+            prop_assert!(below.iter().all(|i| i.end <= at));
+            prop_assert!(above.iter().all(|i| i.start >= at));
+
+            let shifted = a.shift(offset);
+            prop_assert_eq!(a.len(), shifted.len());
+            for x in 0..70 {
+                prop_assert_eq!(a.contains(x), shifted.contains(x + offset));
+            }
+        }
+    }
+}
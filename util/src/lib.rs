@@ -1,21 +1,62 @@
 use anyhow::{anyhow, Result};
 use clap::{Arg, ArgAction, Command};
 use env_logger::Target;
-use log::{trace, LevelFilter};
+use log::{info, trace, LevelFilter};
+use std::fmt::Display;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::OnceLock;
+
+mod bounds;
+pub mod cycle;
+mod direction;
+mod export;
+pub mod flood;
+mod grid;
+mod interval;
+mod neighbor;
+pub mod number;
+mod point;
+pub mod polygon;
+pub mod polynomial;
+mod render;
+pub mod search;
+mod sparse;
+
+pub use bounds::{BoundingBox, MinMax, MinMaxIterator};
+pub use direction::Direction;
+pub use export::{Exporter, ImageFormat, Rgb};
+pub use grid::Grid;
+pub use interval::{Interval, IntervalSet};
+pub use neighbor::{Connectivity, Neighbors};
+pub use number::{greatest_common_divisor, least_common_multiple};
+pub use point::Point;
+pub use render::{Color, Renderer};
+pub use sparse::{SparseGrid, MAX_DENSE_CELLS};
 
 const INPUT_PATH: &str = "input/input";
 const TEST_INPUT_PATH: &str = "input/example";
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+static EXPORT_DIR: OnceLock<PathBuf> = OnceLock::new();
+static EXPORT_FORMAT: OnceLock<ImageFormat> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Input {
     Test,
     Actual,
 }
 
+impl Input {
+    pub fn path(&self) -> &'static str {
+        match self {
+            Self::Test => TEST_INPUT_PATH,
+            Self::Actual => INPUT_PATH,
+        }
+    }
+}
+
 impl FromStr for Input {
     type Err = anyhow::Error;
 
@@ -28,7 +69,71 @@ impl FromStr for Input {
     }
 }
 
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Test => write!(f, "test"),
+            Self::Actual => write!(f, "actual"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "1" | "one" => Ok(Self::One),
+            "2" | "two" => Ok(Self::Two),
+            _ => Err(anyhow!("Invalid part: {}", s)),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::One => write!(f, "1"),
+            Self::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A day's puzzle, parsed from its input lines and solved one part at a time.
+pub trait Solution: TryFrom<Vec<String>, Error = anyhow::Error> {
+    type Answer: Display;
+
+    fn part_one(&self) -> Result<Self::Answer>;
+
+    fn part_two(&self) -> Result<Self::Answer>;
+}
+
+/// Parses the command line, solves the requested part and logs the result.
+pub fn run<S: Solution>() -> Result<()> {
+    let (input, part) = parse_args()?;
+    let solution = S::try_from(input)?;
+
+    let result = match part {
+        Part::One => solution.part_one()?.to_string(),
+        Part::Two => solution.part_two()?.to_string(),
+    };
+
+    info!("Result: {result}");
+
+    Ok(())
+}
+
 pub fn init() -> Result<Vec<String>> {
+    parse_args().map(|(input, _)| input)
+}
+
+fn parse_args() -> Result<(Vec<String>, Part)> {
     let matches = Command::new("advent-of-code")
         .arg(
             Arg::new("verbose")
@@ -48,6 +153,26 @@ pub fn init() -> Result<Vec<String>> {
                     Input::Actual
                 )),
         )
+        .arg(
+            Arg::new("part")
+                .short('p')
+                .long("part")
+                .default_value("1")
+                .help("puzzle part to solve, 1 or 2"),
+        )
+        .arg(
+            Arg::new("export")
+                .short('e')
+                .long("export")
+                .help("directory to write visualisations to"),
+        )
+        .arg(
+            Arg::new("format")
+                .short('f')
+                .long("format")
+                .default_value("png")
+                .help("image format to export visualisations as, png, ppm or svg"),
+        )
         .get_matches();
 
     let verbose = matches.get_flag("verbose");
@@ -55,6 +180,19 @@ pub fn init() -> Result<Vec<String>> {
         .get_one::<String>("input")
         .map(|s| Input::from_str(s))
         .unwrap()?;
+    let part = matches
+        .get_one::<String>("part")
+        .map(|s| Part::from_str(s))
+        .unwrap()?;
+
+    if let Some(dir) = matches.get_one::<String>("export") {
+        let _ = EXPORT_DIR.set(PathBuf::from(dir));
+    }
+    let format = matches
+        .get_one::<String>("format")
+        .map(|s| ImageFormat::from_str(s))
+        .unwrap()?;
+    let _ = EXPORT_FORMAT.set(format);
 
     let log_level = match (input, verbose) {
         (Input::Actual, false) => LevelFilter::Info,
@@ -65,13 +203,24 @@ pub fn init() -> Result<Vec<String>> {
 
     init_logger(log_level)?;
 
-    match input {
+    let input = match input {
         Input::Actual => self::input(),
         Input::Test => self::test_input(),
-    }
+    }?;
+
+    Ok((input, part))
+}
+
+/// Where to write a visualisation with the given name, if `--export` was given, with the extension
+/// of the `--format` chosen.
+pub fn export_path(name: &str) -> Option<PathBuf> {
+    let format = EXPORT_FORMAT.get().copied().unwrap_or(ImageFormat::Png);
+    EXPORT_DIR
+        .get()
+        .map(|dir| dir.join(name).with_extension(format.extension()))
 }
 
-fn init_logger(level: LevelFilter) -> Result<()> {
+pub fn init_logger(level: LevelFilter) -> Result<()> {
     inner_init_logger(Some(level), false)
 }
 
@@ -110,261 +259,3 @@ fn read_lines(path: &'static str) -> Result<Vec<String>> {
         Err(anyhow!("No input: {}", path))
     }
 }
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub enum Neighbor {
-    Right(usize, usize),
-    Left(usize, usize),
-    Upper(usize, usize),
-    Lower(usize, usize),
-    UpperRight(usize, usize),
-    UpperLeft(usize, usize),
-    LowerRight(usize, usize),
-    LowerLeft(usize, usize),
-}
-
-impl From<Neighbor> for (usize, usize) {
-    fn from(value: Neighbor) -> Self {
-        match value {
-            Neighbor::Right(x, y)
-            | Neighbor::Left(x, y)
-            | Neighbor::Upper(x, y)
-            | Neighbor::Lower(x, y)
-            | Neighbor::UpperRight(x, y)
-            | Neighbor::UpperLeft(x, y)
-            | Neighbor::LowerRight(x, y)
-            | Neighbor::LowerLeft(x, y) => (x, y),
-        }
-    }
-}
-
-pub fn grid_neighbors<T>(
-    grid: &[Vec<T>],
-    x: usize,
-    y: usize,
-    include_diagonal: bool,
-) -> Vec<Neighbor> {
-    let mut neighbors = Vec::with_capacity(8);
-
-    {
-        let y = y + 1;
-        if grid.get(y).and_then(|r| r.get(x)).is_some() {
-            neighbors.push(Neighbor::Lower(x, y));
-
-            if include_diagonal {
-                if grid[y].get(x + 1).is_some() {
-                    neighbors.push(Neighbor::LowerRight(x + 1, y));
-                }
-
-                if let Some(x) = x.checked_sub(1) {
-                    neighbors.push(Neighbor::LowerLeft(x, y));
-                }
-            }
-        }
-    }
-
-    if let Some(y) = y.checked_sub(1) {
-        neighbors.push(Neighbor::Upper(x, y));
-
-        if include_diagonal {
-            if grid[y].get(x + 1).is_some() {
-                neighbors.push(Neighbor::UpperRight(x + 1, y));
-            }
-
-            if let Some(x) = x.checked_sub(1) {
-                neighbors.push(Neighbor::UpperLeft(x, y));
-            }
-        }
-    }
-
-    if grid.get(y).and_then(|r| r.get(x + 1)).is_some() {
-        neighbors.push(Neighbor::Right(x + 1, y));
-    }
-
-    if let Some(x) = x.checked_sub(1) {
-        neighbors.push(Neighbor::Left(x, y));
-    }
-
-    neighbors
-}
-
-#[derive(Debug)]
-pub struct MinMax {
-    pub min: Option<usize>,
-    pub max: Option<usize>,
-}
-
-impl FromIterator<usize> for MinMax {
-    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
-        let mut min = None;
-        let mut max = None;
-
-        for i in iter {
-            min = match min {
-                None => Some(i),
-                Some(m) => Some(usize::min(m, i)),
-            };
-            max = match max {
-                None => Some(i),
-                Some(m) => Some(usize::max(m, i)),
-            };
-        }
-
-        MinMax { min, max }
-    }
-}
-
-pub fn greatest_common_divisor(a: usize, b: usize) -> usize {
-    if b > a {
-        greatest_common_divisor(b, a)
-    } else if b == 0 {
-        a
-    } else {
-        greatest_common_divisor(b, a % b)
-    }
-}
-
-pub fn least_common_multiple(a: usize, b: usize) -> usize {
-    (a * b) / greatest_common_divisor(a, b)
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn test_neighbors() {
-        let grid = vec![vec![0; 10]; 10];
-
-        fn assert_eq_ignore_order(mut expected: Vec<Neighbor>, mut neighbors: Vec<Neighbor>) {
-            expected.sort_unstable();
-            neighbors.sort_unstable();
-            assert_eq!(expected, neighbors);
-        }
-
-        assert_eq_ignore_order(
-            vec![Neighbor::Right(1, 0), Neighbor::Lower(0, 1)],
-            grid_neighbors(&grid, 0, 0, false),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::Right(1, 0),
-                Neighbor::Lower(0, 1),
-                Neighbor::LowerRight(1, 1),
-            ],
-            grid_neighbors(&grid, 0, 0, true),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::Left(4, 0),
-                Neighbor::Right(6, 0),
-                Neighbor::Lower(5, 1),
-            ],
-            grid_neighbors(&grid, 5, 0, false),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::Left(4, 0),
-                Neighbor::Right(6, 0),
-                Neighbor::Lower(5, 1),
-                Neighbor::LowerLeft(4, 1),
-                Neighbor::LowerRight(6, 1),
-            ],
-            grid_neighbors(&grid, 5, 0, true),
-        );
-
-        assert_eq_ignore_order(
-            vec![Neighbor::Left(8, 0), Neighbor::Lower(9, 1)],
-            grid_neighbors(&grid, 9, 0, false),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::Left(8, 0),
-                Neighbor::Lower(9, 1),
-                Neighbor::LowerLeft(8, 1),
-            ],
-            grid_neighbors(&grid, 9, 0, true),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::Upper(0, 4),
-                Neighbor::Lower(0, 6),
-                Neighbor::Right(1, 5),
-            ],
-            grid_neighbors(&grid, 0, 5, false),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::Upper(0, 4),
-                Neighbor::Lower(0, 6),
-                Neighbor::Right(1, 5),
-                Neighbor::UpperRight(1, 4),
-                Neighbor::LowerRight(1, 6),
-            ],
-            grid_neighbors(&grid, 0, 5, true),
-        );
-
-        assert_eq_ignore_order(
-            vec![Neighbor::Upper(0, 8), Neighbor::Right(1, 9)],
-            grid_neighbors(&grid, 0, 9, false),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::Upper(0, 8),
-                Neighbor::Right(1, 9),
-                Neighbor::UpperRight(1, 8),
-            ],
-            grid_neighbors(&grid, 0, 9, true),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::Left(3, 4),
-                Neighbor::Upper(4, 3),
-                Neighbor::Lower(4, 5),
-                Neighbor::Right(5, 4),
-            ],
-            grid_neighbors(&grid, 4, 4, false),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::UpperLeft(3, 3),
-                Neighbor::Left(3, 4),
-                Neighbor::LowerLeft(3, 5),
-                Neighbor::Upper(4, 3),
-                Neighbor::Lower(4, 5),
-                Neighbor::UpperRight(5, 3),
-                Neighbor::Right(5, 4),
-                Neighbor::LowerRight(5, 5),
-            ],
-            grid_neighbors(&grid, 4, 4, true),
-        );
-
-        assert_eq_ignore_order(
-            vec![Neighbor::Upper(9, 8), Neighbor::Left(8, 9)],
-            grid_neighbors(&grid, 9, 9, false),
-        );
-
-        assert_eq_ignore_order(
-            vec![
-                Neighbor::UpperLeft(8, 8),
-                Neighbor::Upper(9, 8),
-                Neighbor::Left(8, 9),
-            ],
-            grid_neighbors(&grid, 9, 9, true),
-        );
-    }
-
-    #[test]
-    fn test_greatest_common_divisor() {
-        assert_eq!(6, greatest_common_divisor(48, 18));
-    }
-}
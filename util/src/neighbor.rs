@@ -0,0 +1,288 @@
+use crate::{Direction, Grid, Point};
+
+/// Whether neighbours are only the orthogonally adjacent cells or also the diagonal ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+impl Connectivity {
+    pub fn directions(&self) -> &'static [Direction] {
+        match self {
+            Self::Four => &Direction::ORTHOGONAL,
+            Self::Eight => &Direction::ALL,
+        }
+    }
+}
+
+/// Iterates over the neighbours of a cell and the direction of each without allocating, either
+/// stopping at the grid's edges or wrapping around them as if the grid were tiled infinitely.
+#[derive(Debug, Clone)]
+pub struct Neighbors {
+    width: usize,
+    height: usize,
+    point: Point<usize>,
+    directions: &'static [Direction],
+    wrapping: bool,
+}
+
+impl Neighbors {
+    pub(crate) fn new<T>(
+        grid: &Grid<T>,
+        point: Point<usize>,
+        connectivity: Connectivity,
+        wrapping: bool,
+    ) -> Self {
+        let directions = if grid.contains(point) {
+            connectivity.directions()
+        } else {
+            &[]
+        };
+
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            point,
+            directions,
+            wrapping,
+        }
+    }
+
+    fn step(&self, value: usize, delta: isize, len: usize) -> Option<usize> {
+        if self.wrapping {
+            Some((value as isize + delta).rem_euclid(len as isize) as usize)
+        } else {
+            value.checked_add_signed(delta).filter(|v| *v < len)
+        }
+    }
+}
+
+impl Iterator for Neighbors {
+    type Item = (Direction, Point<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((direction, directions)) = self.directions.split_first() {
+            self.directions = directions;
+
+            let offset = direction.offset();
+            if let (Some(x), Some(y)) = (
+                self.step(self.point.x, offset.x, self.width),
+                self.step(self.point.y, offset.y, self.height),
+            ) {
+                return Some((*direction, Point::new(x, y)));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.directions.len()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::filled(10, 10, 0);
+
+        fn assert_eq_ignore_order(
+            mut expected: Vec<(Direction, Point<usize>)>,
+            mut neighbors: Vec<(Direction, Point<usize>)>,
+        ) {
+            expected.sort_unstable();
+            neighbors.sort_unstable();
+            assert_eq!(expected, neighbors);
+        }
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Right, Point::new(1, 0)),
+                (Direction::Down, Point::new(0, 1)),
+            ],
+            grid.neighbors(Point::new(0, 0), Connectivity::Four)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Right, Point::new(1, 0)),
+                (Direction::Down, Point::new(0, 1)),
+                (Direction::DownRight, Point::new(1, 1)),
+            ],
+            grid.neighbors(Point::new(0, 0), Connectivity::Eight)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Left, Point::new(4, 0)),
+                (Direction::Right, Point::new(6, 0)),
+                (Direction::Down, Point::new(5, 1)),
+            ],
+            grid.neighbors(Point::new(5, 0), Connectivity::Four)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Left, Point::new(4, 0)),
+                (Direction::Right, Point::new(6, 0)),
+                (Direction::Down, Point::new(5, 1)),
+                (Direction::DownLeft, Point::new(4, 1)),
+                (Direction::DownRight, Point::new(6, 1)),
+            ],
+            grid.neighbors(Point::new(5, 0), Connectivity::Eight)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Left, Point::new(8, 0)),
+                (Direction::Down, Point::new(9, 1)),
+            ],
+            grid.neighbors(Point::new(9, 0), Connectivity::Four)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Left, Point::new(8, 0)),
+                (Direction::Down, Point::new(9, 1)),
+                (Direction::DownLeft, Point::new(8, 1)),
+            ],
+            grid.neighbors(Point::new(9, 0), Connectivity::Eight)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Up, Point::new(0, 4)),
+                (Direction::Down, Point::new(0, 6)),
+                (Direction::Right, Point::new(1, 5)),
+            ],
+            grid.neighbors(Point::new(0, 5), Connectivity::Four)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Up, Point::new(0, 4)),
+                (Direction::Down, Point::new(0, 6)),
+                (Direction::Right, Point::new(1, 5)),
+                (Direction::UpRight, Point::new(1, 4)),
+                (Direction::DownRight, Point::new(1, 6)),
+            ],
+            grid.neighbors(Point::new(0, 5), Connectivity::Eight)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Up, Point::new(0, 8)),
+                (Direction::Right, Point::new(1, 9)),
+            ],
+            grid.neighbors(Point::new(0, 9), Connectivity::Four)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Up, Point::new(0, 8)),
+                (Direction::Right, Point::new(1, 9)),
+                (Direction::UpRight, Point::new(1, 8)),
+            ],
+            grid.neighbors(Point::new(0, 9), Connectivity::Eight)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Left, Point::new(3, 4)),
+                (Direction::Up, Point::new(4, 3)),
+                (Direction::Down, Point::new(4, 5)),
+                (Direction::Right, Point::new(5, 4)),
+            ],
+            grid.neighbors(Point::new(4, 4), Connectivity::Four)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::UpLeft, Point::new(3, 3)),
+                (Direction::Left, Point::new(3, 4)),
+                (Direction::DownLeft, Point::new(3, 5)),
+                (Direction::Up, Point::new(4, 3)),
+                (Direction::Down, Point::new(4, 5)),
+                (Direction::UpRight, Point::new(5, 3)),
+                (Direction::Right, Point::new(5, 4)),
+                (Direction::DownRight, Point::new(5, 5)),
+            ],
+            grid.neighbors(Point::new(4, 4), Connectivity::Eight)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::Up, Point::new(9, 8)),
+                (Direction::Left, Point::new(8, 9)),
+            ],
+            grid.neighbors(Point::new(9, 9), Connectivity::Four)
+                .collect(),
+        );
+
+        assert_eq_ignore_order(
+            vec![
+                (Direction::UpLeft, Point::new(8, 8)),
+                (Direction::Up, Point::new(9, 8)),
+                (Direction::Left, Point::new(8, 9)),
+            ],
+            grid.neighbors(Point::new(9, 9), Connectivity::Eight)
+                .collect(),
+        );
+    }
+
+    #[test]
+    fn test_neighbors_out_of_bounds() {
+        let grid = Grid::filled(3, 2, 0);
+
+        assert_eq!(
+            0,
+            grid.neighbors(Point::new(3, 0), Connectivity::Eight)
+                .count()
+        );
+        assert_eq!(
+            0,
+            grid.neighbors(Point::new(0, 2), Connectivity::Eight)
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_wrapping_neighbors() {
+        let grid = Grid::filled(3, 3, 0);
+
+        let mut neighbors = grid
+            .wrapping_neighbors(Point::new(0, 0), Connectivity::Eight)
+            .collect::<Vec<_>>();
+        neighbors.sort_unstable();
+
+        assert_eq!(
+            vec![
+                (Direction::Up, Point::new(0, 2)),
+                (Direction::UpRight, Point::new(1, 2)),
+                (Direction::Right, Point::new(1, 0)),
+                (Direction::DownRight, Point::new(1, 1)),
+                (Direction::Down, Point::new(0, 1)),
+                (Direction::DownLeft, Point::new(2, 1)),
+                (Direction::Left, Point::new(2, 0)),
+                (Direction::UpLeft, Point::new(2, 2)),
+            ],
+            neighbors
+        );
+    }
+}
@@ -0,0 +1,522 @@
+//! Number theory over any primitive integer type. Results are computed on magnitudes in `u128`,
+//! so intermediate values never overflow, and anything that can't fit the result type is `None`.
+
+use std::fmt::{Debug, Display};
+
+pub trait Integer: Copy + Ord + Debug {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn is_negative(self) -> bool;
+
+    /// The absolute value, which always fits in a `u128`.
+    fn magnitude(self) -> u128;
+
+    fn from_magnitude(magnitude: u128) -> Option<Self>;
+}
+
+/// Integers that can be negative, as needed for Bézout coefficients.
+pub trait Signed: Integer {
+    fn checked_neg(self) -> Option<Self>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_rem_euclid(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! unsigned {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn is_negative(self) -> bool {
+                false
+            }
+
+            fn magnitude(self) -> u128 {
+                self as u128
+            }
+
+            fn from_magnitude(magnitude: u128) -> Option<Self> {
+                Self::try_from(magnitude).ok()
+            }
+        }
+    )*};
+}
+
+macro_rules! signed {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn is_negative(self) -> bool {
+                self < 0
+            }
+
+            fn magnitude(self) -> u128 {
+                self.unsigned_abs() as u128
+            }
+
+            fn from_magnitude(magnitude: u128) -> Option<Self> {
+                Self::try_from(magnitude).ok()
+            }
+        }
+
+        impl Signed for $t {
+            fn checked_neg(self) -> Option<Self> {
+                <$t>::checked_neg(self)
+            }
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+
+            fn checked_rem_euclid(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_rem_euclid(self, rhs)
+            }
+        }
+    )*};
+}
+
+unsigned!(u8, u16, u32, u64, u128, usize);
+signed!(i8, i16, i32, i64, i128, isize);
+
+/// The greatest common divisor, which is never negative.
+///
+/// Panics if the result doesn't fit, which only happens for a signed type's minimum value with
+/// itself or zero.
+pub fn greatest_common_divisor<T: Integer>(a: T, b: T) -> T {
+    let gcd = gcd(a.magnitude(), b.magnitude());
+    T::from_magnitude(gcd).unwrap_or_else(|| panic!("gcd({:?}, {:?}) overflows", a, b))
+}
+
+/// The least common multiple, which is never negative, or `None` if it overflows.
+pub fn least_common_multiple<T: Integer>(a: T, b: T) -> Option<T> {
+    let (a, b) = (a.magnitude(), b.magnitude());
+    if a == 0 || b == 0 {
+        return Some(T::ZERO);
+    }
+
+    // Dividing first keeps the product no larger than the result
+    (a / gcd(a, b)).checked_mul(b).and_then(T::from_magnitude)
+}
+
+/// The least common multiple of every value, which is one if there are none.
+pub fn least_common_multiple_of<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, least_common_multiple)
+}
+
+/// The greatest common divisor `g` along with coefficients `x` and `y` such that
+/// `a * x + b * y = g`, or `None` if any of them overflow.
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> Option<(T, T, T)> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let q = old_r.checked_div(r)?;
+        (old_r, r) = (r, old_r.checked_sub(q.checked_mul(r)?)?);
+        (old_x, x) = (x, old_x.checked_sub(q.checked_mul(x)?)?);
+        (old_y, y) = (y, old_y.checked_sub(q.checked_mul(y)?)?);
+    }
+
+    if old_r.is_negative() {
+        Some((
+            old_r.checked_neg()?,
+            old_x.checked_neg()?,
+            old_y.checked_neg()?,
+        ))
+    } else {
+        Some((old_r, old_x, old_y))
+    }
+}
+
+/// The `x` in `0..modulus` such that `a * x ≡ 1 (mod modulus)`, if `a` and `modulus` are coprime.
+pub fn mod_inverse<T: Signed>(a: T, modulus: T) -> Option<T> {
+    if modulus <= T::ZERO {
+        return None;
+    }
+
+    let (g, x, _) = extended_gcd(a.checked_rem_euclid(modulus)?, modulus)?;
+    if g == T::ONE {
+        x.checked_rem_euclid(modulus)
+    } else {
+        None
+    }
+}
+
+/// Why a system of congruences couldn't be solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CongruenceError {
+    /// The congruences contradict each other.
+    NoSolution,
+    NonPositiveModulus,
+    /// The combined modulus, or a value on the way to it, doesn't fit the type.
+    Overflow,
+}
+
+impl Display for CongruenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CongruenceError::NoSolution => write!(f, "Congruences have no common solution"),
+            CongruenceError::NonPositiveModulus => write!(f, "Modulus isn't positive"),
+            CongruenceError::Overflow => write!(f, "Congruences overflow"),
+        }
+    }
+}
+
+impl std::error::Error for CongruenceError {}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)`, whose moduli needn't be coprime,
+/// returning the smallest non-negative solution along with the modulus it repeats with. Unlike
+/// the rest of this module it says why it failed, since a system without a solution is an
+/// answer in itself while an overflow isn't.
+pub fn chinese_remainder<T: Signed>(
+    congruences: impl IntoIterator<Item = (T, T)>,
+) -> Result<(T, T), CongruenceError> {
+    use CongruenceError::*;
+
+    congruences
+        .into_iter()
+        .try_fold((T::ZERO, T::ONE), |(r1, m1), (r2, m2)| {
+            if m2 <= T::ZERO {
+                return Err(NonPositiveModulus);
+            }
+
+            let (g, p, _) = extended_gcd(m1, m2).ok_or(Overflow)?;
+            let difference = r2.checked_sub(r1).ok_or(Overflow)?;
+            if difference.checked_rem_euclid(g).ok_or(Overflow)? != T::ZERO {
+                return Err(NoSolution);
+            }
+
+            // p is the inverse of m1 / g modulo m2 / g, so stepping r1 by m1 * k reaches r2
+            let solve = || {
+                let m2_g = m2.checked_div(g)?;
+                let k = mul_rem(difference.checked_div(g)?, p, m2_g)?;
+                let modulus = m1.checked_div(g)?.checked_mul(m2)?;
+                let residue = r1
+                    .checked_add(m1.checked_mul(k)?)?
+                    .checked_rem_euclid(modulus)?;
+
+                Some((residue, modulus))
+            };
+            solve().ok_or(Overflow)
+        })
+}
+
+/// `base` raised to `exponent`, reduced into `0..modulus`. Returns `None` unless the modulus is
+/// positive and the exponent isn't negative.
+pub fn mod_pow<T: Integer>(base: T, exponent: T, modulus: T) -> Option<T> {
+    if modulus <= T::ZERO || exponent.is_negative() {
+        return None;
+    }
+
+    let m = modulus.magnitude();
+    let mut residue = base.magnitude() % m;
+    if base.is_negative() && residue != 0 {
+        residue = m - residue;
+    }
+
+    let mut exponent = exponent.magnitude();
+    let mut result = 1 % m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, residue, m);
+        }
+        residue = mul_mod(residue, residue, m);
+        exponent >>= 1;
+    }
+
+    T::from_magnitude(result)
+}
+
+/// `a * b` reduced into `0..modulus`, without overflowing when the product does.
+fn mul_rem<T: Signed>(a: T, b: T, modulus: T) -> Option<T> {
+    let m = modulus.magnitude();
+    let (a, b) = (
+        a.checked_rem_euclid(modulus)?.magnitude(),
+        b.checked_rem_euclid(modulus)?.magnitude(),
+    );
+    T::from_magnitude(mul_mod(a, b, m))
+}
+
+/// An exact fraction in lowest terms, with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self::integer(0);
+    pub const ONE: Self = Self::integer(1);
+
+    pub const fn integer(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    /// Reduces the fraction, or returns `None` if the denominator is zero or it overflows.
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+
+        // Reduce the magnitudes, since the gcd of `i128::MIN` with itself or zero doesn't fit
+        let (n, d) = (numerator.unsigned_abs(), denominator.unsigned_abs());
+        let gcd = gcd(n, d);
+        let numerator = if (numerator < 0) != (denominator < 0) {
+            0i128.checked_sub_unsigned(n / gcd)?
+        } else {
+            i128::try_from(n / gcd).ok()?
+        };
+        let denominator = i128::try_from(d / gcd).ok()?;
+
+        Some(Self {
+            numerator,
+            denominator,
+        })
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let lcm = least_common_multiple(self.denominator, rhs.denominator)?;
+        let lhs = self.numerator.checked_mul(lcm / self.denominator)?;
+        let rhs = rhs.numerator.checked_mul(lcm / rhs.denominator)?;
+        Self::new(lhs.checked_add(rhs)?, lcm)
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // Cross-cancelling first keeps the products as small as possible
+        let a = greatest_common_divisor(self.numerator, rhs.denominator);
+        let b = greatest_common_divisor(rhs.numerator, self.denominator);
+        Self::new(
+            (self.numerator / a).checked_mul(rhs.numerator / b)?,
+            (self.denominator / b).checked_mul(rhs.denominator / a)?,
+        )
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_mul(Self::new(rhs.denominator, rhs.numerator)?)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// `a * b % m` for `a` and `b` below `m`, doubling and adding when the product overflows.
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    let (mut a, mut b, mut result) = (a, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+/// `(a + b) % m` for `a` and `b` below `m`.
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_greatest_common_divisor() {
+        assert_eq!(6, greatest_common_divisor(48usize, 18));
+        assert_eq!(6, greatest_common_divisor(-48i32, 18));
+        assert_eq!(5, greatest_common_divisor(0u8, 5));
+        assert_eq!(0, greatest_common_divisor(0i64, 0));
+        assert_eq!(1 << 100, greatest_common_divisor(3u128 << 100, 1 << 101));
+        assert_eq!(1 << 62, greatest_common_divisor(i64::MIN, 1 << 62));
+    }
+
+    #[test]
+    fn test_least_common_multiple() {
+        assert_eq!(Some(36), least_common_multiple(12usize, 18));
+        assert_eq!(Some(36), least_common_multiple(-12i64, 18));
+        assert_eq!(Some(0), least_common_multiple(0u32, 7));
+
+        // The product of the arguments overflows, but the multiple doesn't
+        assert_eq!(Some(1 << 63), least_common_multiple(1u64 << 63, 1 << 62));
+        assert_eq!(None, least_common_multiple(u64::MAX, u64::MAX - 1));
+        assert_eq!(None, least_common_multiple(i8::MIN, 3));
+
+        assert_eq!(Some(2520), least_common_multiple_of(1u64..=10));
+        assert_eq!(Some(1), least_common_multiple_of(Vec::<u8>::new()));
+        assert_eq!(None, least_common_multiple_of(1u8..=10));
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        assert_eq!(Some((2, -9, 47)), extended_gcd(240i64, 46));
+        assert_eq!(Some((6, -1, 0)), extended_gcd(-6i32, 0));
+        assert_eq!(None, extended_gcd(i8::MIN, -1));
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(Some(4), mod_inverse(3i64, 11));
+        assert_eq!(Some(7), mod_inverse(-3i64, 11));
+        assert_eq!(None, mod_inverse(6i64, 9));
+        assert_eq!(None, mod_inverse(3i64, 0));
+
+        assert_eq!(Some(445), mod_pow(4u32, 13, 497));
+        assert_eq!(Some(1), mod_pow(-1i32, 2, 7));
+        assert_eq!(Some(6), mod_pow(-1i32, 3, 7));
+        assert_eq!(Some(0), mod_pow(5u8, 0, 1));
+        assert_eq!(None, mod_pow(5i8, -1, 7));
+
+        // Fermat's little theorem with a modulus whose squares overflow u128
+        let p = u128::MAX - 158;
+        assert_eq!(Some(1), mod_pow(3u128, p - 1, p));
+    }
+
+    #[test]
+    fn test_chinese_remainder() {
+        assert_eq!(
+            Ok((23, 105)),
+            chinese_remainder([(2i64, 3), (3, 5), (2, 7)])
+        );
+        assert_eq!(Ok((10, 12)), chinese_remainder([(4i64, 6), (2, 4)]));
+        assert_eq!(Ok((0, 1)), chinese_remainder(Vec::<(i64, i64)>::new()));
+        assert_eq!(Ok((3, 4)), chinese_remainder([(-1i64, 4)]));
+        assert_eq!(
+            Err(CongruenceError::NoSolution),
+            chinese_remainder([(1i64, 4), (2, 6)])
+        );
+        assert_eq!(
+            Err(CongruenceError::NonPositiveModulus),
+            chinese_remainder([(1i64, 0)])
+        );
+        assert_eq!(
+            Err(CongruenceError::Overflow),
+            chinese_remainder([(0i8, 11), (0, 13)])
+        );
+    }
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(2, 4).unwrap();
+        let third = Rational::new(-1, -3).unwrap();
+
+        assert_eq!("1/2", half.to_string());
+        assert_eq!("-3", Rational::new(6, -2).unwrap().to_string());
+        assert_eq!(Some(Rational::new(5, 6).unwrap()), half.checked_add(third));
+        assert_eq!(Some(Rational::new(1, 6).unwrap()), half.checked_sub(third));
+        assert_eq!(Some(Rational::new(1, 6).unwrap()), half.checked_mul(third));
+        assert_eq!(Some(Rational::new(3, 2).unwrap()), half.checked_div(third));
+        assert_eq!(None, half.checked_div(Rational::ZERO));
+        assert_eq!(None, Rational::new(1, 0));
+        assert_eq!(Some(Rational::ONE), Rational::new(i128::MIN, i128::MIN));
+        assert_eq!(Some(Rational::ZERO), Rational::new(0, i128::MIN));
+        assert_eq!(None, Rational::new(i128::MIN, -1));
+        assert_eq!(
+            Some(Rational::integer(i128::MIN)),
+            Rational::new(i128::MIN, 1)
+        );
+        assert_eq!(Some(half), Rational::new(i128::MIN / 2, i128::MIN));
+        assert_eq!(
+            None,
+            Rational::integer(i128::MAX).checked_add(Rational::ONE)
+        );
+        assert!(Rational::new(4, 2).unwrap().is_integer());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_chinese_remainder(x in 0..1_000_000i64, m1 in 1..1000i64, m2 in 1..1000i64) {
+            let (r, m) = chinese_remainder([(x % m1, m1), (x % m2, m2)]).unwrap();
+            prop_assert_eq!(least_common_multiple(m1, m2), Some(m));
+            prop_assert_eq!(x % m, r);
+        }
+
+        #[test]
+        fn prop_bezout_identity(a in any::<i32>(), b in any::<i32>()) {
+            let (g, x, y) = extended_gcd(a as i64, b as i64).unwrap();
+            prop_assert_eq!(greatest_common_divisor(a as i64, b as i64), g);
+            prop_assert_eq!(g as i128, a as i128 * x as i128 + b as i128 * y as i128);
+        }
+
+        #[test]
+        fn prop_lcm_is_common_multiple(a in 1..u32::MAX, b in 1..u32::MAX) {
+            let lcm = least_common_multiple(a as u64, b as u64).unwrap();
+            prop_assert_eq!(0, lcm % a as u64);
+            prop_assert_eq!(0, lcm % b as u64);
+            prop_assert_eq!(a as u64 * b as u64, lcm * greatest_common_divisor(a, b) as u64);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Direction, Grid};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A position or offset, `Point<usize>` within a `Grid` and `Point<isize>` anywhere else.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Point<isize> {
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+
+    pub fn checked_step(self, direction: Direction) -> Option<Self> {
+        let offset = direction.offset();
+        Some(Self::new(
+            self.x.checked_add(offset.x)?,
+            self.y.checked_add(offset.y)?,
+        ))
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> usize {
+        usize::max(self.x.abs_diff(other.x), self.y.abs_diff(other.y))
+    }
+}
+
+impl Point<usize> {
+    /// Steps in the given direction, unless that would leave the first quadrant.
+    pub fn checked_step(self, direction: Direction) -> Option<Self> {
+        let offset = direction.offset();
+        Some(Self::new(
+            self.x.checked_add_signed(offset.x)?,
+            self.y.checked_add_signed(offset.y)?,
+        ))
+    }
+
+    /// Steps in the given direction, unless that would leave the grid.
+    pub fn step_within<T>(self, direction: Direction, grid: &Grid<T>) -> Option<Self> {
+        self.checked_step(direction).filter(|p| grid.contains(*p))
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> usize {
+        usize::max(self.x.abs_diff(other.x), self.y.abs_diff(other.y))
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(value: Point<T>) -> Self {
+        (value.x, value.y)
+    }
+}
+
+impl TryFrom<Point<isize>> for Point<usize> {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(value: Point<isize>) -> Result<Self, Self::Error> {
+        Ok(Self::new(value.x.try_into()?, value.y.try_into()?))
+    }
+}
+
+impl TryFrom<Point<usize>> for Point<isize> {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(value: Point<usize>) -> Result<Self, Self::Error> {
+        Ok(Self::new(value.x.try_into()?, value.y.try_into()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let p = Point::new(3isize, -2);
+
+        assert_eq!(Point::new(4, -4), p + Point::new(1, -2));
+        assert_eq!(Point::new(2, 0), p - Point::new(1, -2));
+        assert_eq!(Point::new(6, -4), p * 2);
+        assert_eq!(Point::new(-3, 2), -p);
+        assert_eq!(Point::new(3, -3), p.step(Direction::Up));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(-1isize, 2);
+        let b = Point::new(3, -5);
+
+        assert_eq!(11, a.manhattan_distance(&b));
+        assert_eq!(7, a.chebyshev_distance(&b));
+
+        let a = Point::new(1usize, 2);
+        let b = Point::new(4, 0);
+
+        assert_eq!(5, a.manhattan_distance(&b));
+        assert_eq!(3, a.chebyshev_distance(&b));
+    }
+
+    #[test]
+    fn test_steps() {
+        let grid = Grid::filled(2, 2, 0);
+        let p = Point::new(0usize, 1);
+
+        assert_eq!(None, p.checked_step(Direction::Left));
+        assert_eq!(
+            None,
+            Point::new(isize::MAX, 0).checked_step(Direction::Right)
+        );
+        assert_eq!(Some(Point::new(0, 2)), p.checked_step(Direction::Down));
+        assert_eq!(None, p.step_within(Direction::Down, &grid));
+        assert_eq!(
+            Some(Point::new(1, 0)),
+            p.step_within(Direction::UpRight, &grid)
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(
+            Ok(Point::new(1usize, 2)),
+            Point::try_from(Point::new(1isize, 2))
+        );
+        assert!(Point::<usize>::try_from(Point::new(-1isize, 2)).is_err());
+    }
+}
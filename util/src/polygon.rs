@@ -0,0 +1,92 @@
+//! Measurements of simple polygons over integer vertices, given in order around the boundary.
+//! Areas are doubled so that they stay exact for polygons with half-integer areas, and are summed
+//! in `i128` so coordinates far beyond `i32` don't overflow.
+
+use crate::{greatest_common_divisor, Point};
+
+/// Twice the signed area by the shoelace formula, positive when the vertices run
+/// counter-clockwise with `y` pointing up (clockwise on a grid where `y` points down).
+pub fn signed_double_area(vertices: &[Point<i64>]) -> i128 {
+    edges(vertices)
+        .map(|(a, b)| a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128)
+        .sum()
+}
+
+pub fn double_area(vertices: &[Point<i64>]) -> i128 {
+    signed_double_area(vertices).abs()
+}
+
+/// The number of lattice points on the boundary, including the vertices.
+pub fn boundary_points(vertices: &[Point<i64>]) -> i128 {
+    edges(vertices)
+        .map(|(a, b)| greatest_common_divisor(b.x as i128 - a.x as i128, b.y as i128 - a.y as i128))
+        .sum()
+}
+
+/// The number of lattice points strictly inside, by Pick's theorem.
+pub fn interior_points(vertices: &[Point<i64>]) -> i128 {
+    (double_area(vertices) - boundary_points(vertices) + 2) / 2
+}
+
+/// The number of lattice points inside or on the boundary, such as the cells dug out by a dig
+/// plan.
+pub fn lattice_points(vertices: &[Point<i64>]) -> i128 {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+pub fn perimeter(vertices: &[Point<i64>]) -> f64 {
+    edges(vertices)
+        .map(|(a, b)| {
+            let dx = (b.x as i128 - a.x as i128) as f64;
+            let dy = (b.y as i128 - a.y as i128) as f64;
+            dx.hypot(dy)
+        })
+        .sum()
+}
+
+fn edges(vertices: &[Point<i64>]) -> impl Iterator<Item = (&Point<i64>, &Point<i64>)> {
+    vertices.iter().zip(vertices.iter().cycle().skip(1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn points(vertices: &[(i64, i64)]) -> Vec<Point<i64>> {
+        vertices.iter().copied().map(Point::from).collect()
+    }
+
+    #[test]
+    fn test_square() {
+        let square = points(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+
+        assert_eq!(32, signed_double_area(&square));
+        assert_eq!(16, boundary_points(&square));
+        assert_eq!(9, interior_points(&square));
+        assert_eq!(25, lattice_points(&square));
+        assert_eq!(16.0, perimeter(&square));
+
+        let reversed = square.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(-32, signed_double_area(&reversed));
+        assert_eq!(32, double_area(&reversed));
+    }
+
+    #[test]
+    fn test_triangle() {
+        let triangle = points(&[(0, 0), (3, 1), (1, 2)]);
+
+        assert_eq!(5, double_area(&triangle));
+        assert_eq!(3, boundary_points(&triangle));
+        assert_eq!(2, interior_points(&triangle));
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        let max = 1 << 60;
+        let square = points(&[(-max, -max), (max, -max), (max, max), (-max, max)]);
+
+        let side = 2 * max as i128;
+        assert_eq!(2 * side * side, double_area(&square));
+        assert_eq!(4 * side, boundary_points(&square));
+    }
+}
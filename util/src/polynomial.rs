@@ -0,0 +1,296 @@
+//! Fitting polynomials to sequences by finite differences. A sequence whose differences, taken
+//! enough times, are all zero is a polynomial in its index, and Newton's forward difference
+//! formula evaluates it at any index, before or far beyond the values it was fitted to.
+
+use crate::number::Rational;
+use std::error::Error;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolynomialError {
+    Empty,
+    /// The differences ran out of values before they were all zero.
+    NotConverging,
+    Overflow,
+}
+
+impl Display for PolynomialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "No values to fit"),
+            Self::NotConverging => write!(f, "Differences never reach zero"),
+            Self::Overflow => write!(f, "Arithmetic overflow"),
+        }
+    }
+}
+
+impl Error for PolynomialError {}
+
+/// A polynomial in Newton's forward form, `f(x) = Σ Δᵏf(0) · C(x, k)`, where `Δᵏf(0)` is the first
+/// of the `k`th differences of the values at `x = 0, 1, 2...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Newton {
+    differences: Vec<i128>,
+}
+
+impl Newton {
+    /// Fits the values at indices `0, 1, 2...`, failing unless some level of differences is all
+    /// zeros.
+    pub fn fit(values: &[i128]) -> Result<Self, PolynomialError> {
+        if values.is_empty() {
+            return Err(PolynomialError::Empty);
+        }
+
+        let mut differences = Vec::new();
+        let mut level = values.to_vec();
+        while !level.iter().all(|v| *v == 0) {
+            differences.push(level[0]);
+
+            level = level
+                .windows(2)
+                .map(|w| w[1].checked_sub(w[0]))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(PolynomialError::Overflow)?;
+
+            if level.is_empty() {
+                return Err(PolynomialError::NotConverging);
+            }
+        }
+
+        Ok(Self { differences })
+    }
+
+    /// The highest power of `x`, taking the zero polynomial to be constant.
+    pub fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
+
+    /// The first value of each level of differences, starting with the first value itself.
+    pub fn differences(&self) -> &[i128] {
+        &self.differences
+    }
+
+    pub fn evaluate(&self, x: i128) -> Result<i128, PolynomialError> {
+        let mut value: i128 = 0;
+        // C(x, k), which stays an integer even for negative x
+        let mut binomial: i128 = 1;
+
+        for (k, difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                let k = k as i128;
+                binomial = x
+                    .checked_sub(k - 1)
+                    .and_then(|factor| binomial.checked_mul(factor))
+                    .ok_or(PolynomialError::Overflow)?
+                    / k;
+            }
+
+            value = difference
+                .checked_mul(binomial)
+                .and_then(|term| value.checked_add(term))
+                .ok_or(PolynomialError::Overflow)?;
+        }
+
+        Ok(value)
+    }
+
+    /// Expands into powers of `x`, whose coefficients may be fractions even though every value
+    /// at an integer is an integer.
+    pub fn to_polynomial(&self) -> Result<Polynomial, PolynomialError> {
+        let mut coefficients = vec![Rational::ZERO; self.differences.len()];
+        // C(x, k) = x (x - 1) ... (x - k + 1) / k!, as coefficients of increasing powers
+        let mut binomial = vec![Rational::ONE];
+
+        for (k, difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                let root = Rational::integer(k as i128 - 1);
+                let divisor = Rational::integer(k as i128);
+
+                // Multiply by (x - root) / k
+                let mut next = vec![Rational::ZERO; binomial.len() + 1];
+                for (power, coefficient) in binomial.iter().enumerate() {
+                    let shifted = coefficient.checked_div(divisor);
+                    let scaled = shifted.and_then(|c| c.checked_mul(root));
+                    next[power + 1] = shifted
+                        .and_then(|c| next[power + 1].checked_add(c))
+                        .ok_or(PolynomialError::Overflow)?;
+                    next[power] = scaled
+                        .and_then(|c| next[power].checked_sub(c))
+                        .ok_or(PolynomialError::Overflow)?;
+                }
+                binomial = next;
+            }
+
+            let difference = Rational::integer(*difference);
+            for (power, coefficient) in binomial.iter().enumerate() {
+                coefficients[power] = coefficient
+                    .checked_mul(difference)
+                    .and_then(|term| coefficients[power].checked_add(term))
+                    .ok_or(PolynomialError::Overflow)?;
+            }
+        }
+
+        Ok(Polynomial::new(coefficients))
+    }
+}
+
+/// A polynomial with exact coefficients, lowest power first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    coefficients: Vec<Rational>,
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<Rational>) -> Self {
+        while coefficients.last() == Some(&Rational::ZERO) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coefficients
+    }
+
+    /// The highest power of `x`, taking the zero polynomial to be constant.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    pub fn evaluate(&self, x: Rational) -> Result<Rational, PolynomialError> {
+        // Horner's method
+        self.coefficients
+            .iter()
+            .rev()
+            .try_fold(Rational::ZERO, |value, coefficient| {
+                value.checked_mul(x)?.checked_add(*coefficient)
+            })
+            .ok_or(PolynomialError::Overflow)
+    }
+}
+
+/// Writes the terms from the highest power down, such as `(1/2)x^2 + (3/2)x + 1`.
+impl Display for Polynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut terms = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| **c != Rational::ZERO)
+            .peekable();
+
+        if terms.peek().is_none() {
+            return write!(f, "0");
+        }
+
+        let mut first = true;
+        for (power, coefficient) in terms {
+            let negative = coefficient.numerator() < 0;
+            match (first, negative) {
+                (true, true) => write!(f, "-")?,
+                (true, false) => (),
+                (false, true) => write!(f, " - ")?,
+                (false, false) => write!(f, " + ")?,
+            }
+            first = false;
+
+            let magnitude = if negative {
+                coefficient.checked_neg().unwrap_or(*coefficient)
+            } else {
+                *coefficient
+            };
+
+            match (power, magnitude) {
+                (0, m) => write!(f, "{}", m)?,
+                (_, m) if m == Rational::ONE => (),
+                (_, m) if m.is_integer() => write!(f, "{}", m)?,
+                (_, m) => write!(f, "({})", m)?,
+            }
+
+            match power {
+                0 => (),
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{}", power)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fit() -> Result<(), PolynomialError> {
+        let linear = Newton::fit(&[0, 3, 6, 9, 12, 15])?;
+        assert_eq!(1, linear.degree());
+        assert_eq!(&[0, 3], linear.differences());
+
+        let triangular = Newton::fit(&[1, 3, 6, 10, 15, 21])?;
+        assert_eq!(2, triangular.degree());
+
+        assert_eq!(0, Newton::fit(&[0, 0])?.degree());
+        assert_eq!(0, Newton::fit(&[7, 7])?.degree());
+
+        assert_eq!(Err(PolynomialError::Empty), Newton::fit(&[]));
+        assert_eq!(Err(PolynomialError::NotConverging), Newton::fit(&[5]));
+        assert_eq!(
+            Err(PolynomialError::NotConverging),
+            Newton::fit(&[1, 2, 4, 8, 16])
+        );
+        assert_eq!(
+            Err(PolynomialError::Overflow),
+            Newton::fit(&[i128::MIN, i128::MAX])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_evaluate() -> Result<(), PolynomialError> {
+        let sequence = Newton::fit(&[10, 13, 16, 21, 30, 45])?;
+        assert_eq!(68, sequence.evaluate(6)?);
+        assert_eq!(5, sequence.evaluate(-1)?);
+
+        // n³ - 2n, far from where it was fitted in both directions
+        let cubic = Newton::fit(&[0, -1, 4, 21, 56])?;
+        let f = |n: i128| n * n * n - 2 * n;
+        for x in [-1_000_000, -7, -1, 0, 3, 1_000_000] {
+            assert_eq!(f(x), cubic.evaluate(x)?);
+        }
+
+        assert_eq!(0, Newton::fit(&[0, 0])?.evaluate(1_000)?);
+        assert_eq!(
+            Err(PolynomialError::Overflow),
+            cubic.evaluate(i128::MAX / 2)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_polynomial() -> Result<(), PolynomialError> {
+        let triangular = Newton::fit(&[1, 3, 6, 10, 15, 21])?.to_polynomial()?;
+        assert_eq!(2, triangular.degree());
+        assert_eq!("(1/2)x^2 + (3/2)x + 1", triangular.to_string());
+        assert_eq!(
+            Rational::integer(28),
+            triangular.evaluate(Rational::integer(6))?
+        );
+
+        let cubic = Newton::fit(&[0, -1, 4, 21, 56])?.to_polynomial()?;
+        assert_eq!("x^3 - 2x", cubic.to_string());
+
+        assert_eq!(
+            "-3x + 7",
+            Newton::fit(&[7, 4, 1])?.to_polynomial()?.to_string()
+        );
+        assert_eq!("0", Newton::fit(&[0, 0])?.to_polynomial()?.to_string());
+        assert_eq!(0, Newton::fit(&[0, 0])?.to_polynomial()?.degree());
+
+        Ok(())
+    }
+}
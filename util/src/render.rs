@@ -0,0 +1,200 @@
+use crate::{Grid, Point};
+use std::collections::HashSet;
+use std::io::{self, IsTerminal};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::White => 37,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Layer {
+    points: HashSet<Point<usize>>,
+    color: Color,
+    glyph: Option<char>,
+}
+
+/// Draws a grid as text with highlighted layers, such as a path, a region and a cursor. Later
+/// layers are drawn over earlier ones.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    chars: Grid<char>,
+    layers: Vec<Layer>,
+    box_drawing: bool,
+    ansi: bool,
+}
+
+impl Renderer {
+    /// Colours are only used when stdout is a terminal.
+    pub fn new<T>(grid: &Grid<T>) -> Self
+    where
+        for<'a> char: From<&'a T>,
+    {
+        Self::from_chars(Grid::from_fn(grid.width(), grid.height(), |p| {
+            char::from(&grid[p])
+        }))
+    }
+
+    /// Draws a grid of characters which has already been converted, such as from a
+    /// [`SparseGrid`](crate::SparseGrid).
+    pub fn from_chars(chars: Grid<char>) -> Self {
+        Self {
+            chars,
+            layers: Vec::new(),
+            box_drawing: false,
+            ansi: io::stdout().is_terminal(),
+        }
+    }
+
+    /// Highlights the given points by colouring their cells.
+    pub fn layer(self, points: impl IntoIterator<Item = Point<usize>>, color: Color) -> Self {
+        self.add_layer(points, color, None)
+    }
+
+    /// Highlights the given points by drawing them as `glyph`, which also marks them without
+    /// colour.
+    pub fn glyph_layer(
+        self,
+        points: impl IntoIterator<Item = Point<usize>>,
+        color: Color,
+        glyph: char,
+    ) -> Self {
+        self.add_layer(points, color, Some(glyph))
+    }
+
+    /// Draws the pipe characters `|-LJ7F` with box-drawing characters.
+    pub fn box_drawing(mut self, box_drawing: bool) -> Self {
+        self.box_drawing = box_drawing;
+        self
+    }
+
+    pub fn ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+
+        for y in 0..self.chars.height() {
+            if y > 0 {
+                output.push('\n');
+            }
+
+            for x in 0..self.chars.width() {
+                let point = Point::new(x, y);
+                let mut c = self.chars[point];
+                if self.box_drawing {
+                    c = box_drawing(c);
+                }
+
+                match self.layers.iter().rev().find(|l| l.points.contains(&point)) {
+                    Some(layer) => {
+                        let c = layer.glyph.unwrap_or(c);
+                        if self.ansi {
+                            output.push_str(&format!(
+                                "\x1b[{}m{}\x1b[0m",
+                                layer.color.ansi_code(),
+                                c
+                            ));
+                        } else {
+                            output.push(c);
+                        }
+                    }
+                    None => output.push(c),
+                }
+            }
+        }
+
+        output
+    }
+
+    fn add_layer(
+        mut self,
+        points: impl IntoIterator<Item = Point<usize>>,
+        color: Color,
+        glyph: Option<char>,
+    ) -> Self {
+        self.layers.push(Layer {
+            points: points.into_iter().collect(),
+            color,
+            glyph,
+        });
+        self
+    }
+}
+
+fn box_drawing(c: char) -> char {
+    match c {
+        '|' => '│',
+        '-' => '─',
+        'L' => '└',
+        'J' => '┘',
+        '7' => '┐',
+        'F' => '┌',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy)]
+    struct Cell(char);
+
+    impl From<&Cell> for char {
+        fn from(value: &Cell) -> Self {
+            value.0
+        }
+    }
+
+    fn grid() -> Grid<Cell> {
+        Grid::new(3, 2, "F-7L-J".chars().map(Cell).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_plain() {
+        let grid = grid();
+
+        let renderer = Renderer::new(&grid)
+            .ansi(false)
+            .layer([Point::new(0, 0)], Color::Green)
+            .glyph_layer([Point::new(1, 1)], Color::Red, '*');
+
+        assert_eq!("F-7\nL*J", renderer.render());
+        assert_eq!("┌─┐\n└*┘", renderer.box_drawing(true).render());
+    }
+
+    #[test]
+    fn test_ansi() {
+        let grid = grid();
+
+        let renderer = Renderer::new(&grid)
+            .ansi(true)
+            .layer([Point::new(0, 0), Point::new(1, 0)], Color::Green)
+            .layer([Point::new(1, 0)], Color::Red);
+
+        assert_eq!("\x1b[32mF\x1b[0m\x1b[31m-\x1b[0m7\nL-J", renderer.render());
+    }
+}
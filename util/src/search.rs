@@ -0,0 +1,255 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The costs from a start to every state reached, along with the step taken into each state so
+/// that paths can be reconstructed.
+#[derive(Debug, Clone)]
+pub struct Search<S, C> {
+    distances: HashMap<S, C>,
+    parents: HashMap<S, S>,
+}
+
+impl<S: Hash + Eq + Clone, C: Copy> Search<S, C> {
+    pub fn distance(&self, state: &S) -> Option<C> {
+        self.distances.get(state).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<S, C> {
+        &self.distances
+    }
+
+    /// Reconstructs the path from the start to the given state, including both ends.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Breadth-first search, where every step costs one.
+pub fn bfs<S, F, I>(start: S, mut neighbors: F) -> Search<S, usize>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut parents = HashMap::new();
+
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        let distance = distances[&state];
+
+        for next in neighbors(&state) {
+            if let Entry::Vacant(e) = distances.entry(next.clone()) {
+                e.insert(distance + 1);
+                parents.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Search { distances, parents }
+}
+
+/// Dijkstra's algorithm, where `neighbors` returns each next state along with the cost of
+/// stepping into it.
+pub fn dijkstra<S, C, F, I>(start: S, neighbors: F) -> Search<S, C>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    best_first(start, neighbors, |_| C::default(), |_| false).0
+}
+
+/// A* search towards the first state satisfying `is_goal`, returning the path to it and its cost.
+/// The heuristic must never overestimate the remaining cost for the path to be the cheapest.
+pub fn astar<S, C, F, I, H, G>(
+    start: S,
+    neighbors: F,
+    heuristic: H,
+    is_goal: G,
+) -> Option<(Vec<S>, C)>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let (search, goal) = best_first(start, neighbors, heuristic, is_goal);
+    let goal = goal?;
+
+    Some((search.path_to(&goal)?, search.distance(&goal)?))
+}
+
+struct Queued<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for Queued<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S, C: Ord> Eq for Queued<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Queued<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Queued<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.cmp(&other.priority)
+    }
+}
+
+fn best_first<S, C, F, I, H, G>(
+    start: S,
+    mut neighbors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> (Search<S, C>, Option<S>)
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut distances = HashMap::from([(start.clone(), C::default())]);
+    let mut parents = HashMap::new();
+
+    let mut queue = BinaryHeap::from([Reverse(Queued {
+        priority: heuristic(&start),
+        cost: C::default(),
+        state: start,
+    })]);
+
+    while let Some(Reverse(Queued { cost, state, .. })) = queue.pop() {
+        if distances.get(&state).is_some_and(|d| *d < cost) {
+            // A cheaper way to this state was already expanded
+            continue;
+        }
+
+        if is_goal(&state) {
+            return (Search { distances, parents }, Some(state));
+        }
+
+        for (next, step) in neighbors(&state) {
+            let next_cost = cost + step;
+            if distances.get(&next).is_some_and(|d| *d <= next_cost) {
+                continue;
+            }
+
+            distances.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), state.clone());
+            queue.push(Reverse(Queued {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                state: next,
+            }));
+        }
+    }
+
+    (Search { distances, parents }, None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Connectivity, Grid, Point};
+
+    fn maze() -> Grid<char> {
+        Grid::from_lines(["..#.", ".##.", "....", "#.#."]).unwrap()
+    }
+
+    fn open_neighbors(grid: &Grid<char>, point: &Point<usize>) -> Vec<Point<usize>> {
+        grid.neighbors(*point, Connectivity::Four)
+            .filter(|(_, n)| grid[*n] == '.')
+            .map(|(_, n)| n)
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let search = bfs(Point::new(0, 0), |p| open_neighbors(&grid, p));
+
+        assert_eq!(Some(5), search.distance(&Point::new(3, 2)));
+        assert_eq!(Some(7), search.distance(&Point::new(3, 0)));
+        assert_eq!(None, search.distance(&Point::new(2, 0)));
+        assert_eq!(
+            Some(vec![
+                Point::new(0, 0),
+                Point::new(0, 1),
+                Point::new(0, 2),
+                Point::new(1, 2),
+                Point::new(1, 3),
+            ]),
+            search.path_to(&Point::new(1, 3))
+        );
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // Going through `b` is longer but cheaper than going straight to `c`
+        let edges = HashMap::from([
+            ('a', vec![('b', 1), ('c', 10)]),
+            ('b', vec![('c', 2)]),
+            ('c', vec![('d', 1)]),
+        ]);
+
+        let search = dijkstra('a', |s| edges.get(s).cloned().unwrap_or_default());
+
+        assert_eq!(Some(3), search.distance(&'c'));
+        assert_eq!(Some(4), search.distance(&'d'));
+        assert_eq!(Some(vec!['a', 'b', 'c', 'd']), search.path_to(&'d'));
+    }
+
+    #[test]
+    fn test_astar() {
+        let grid = maze();
+        let goal = Point::new(3, 0);
+
+        let (path, cost) = astar(
+            Point::new(0, 0),
+            |p| open_neighbors(&grid, p).into_iter().map(|n| (n, 1)),
+            |p| p.manhattan_distance(&goal),
+            |p| *p == goal,
+        )
+        .unwrap();
+
+        assert_eq!(7, cost);
+        assert_eq!(8, path.len());
+        assert_eq!(Some(&goal), path.last());
+
+        assert!(astar(
+            Point::new(0, 0),
+            |p| open_neighbors(&grid, p).into_iter().map(|n| (n, 1)),
+            |_| 0,
+            |p| *p == Point::new(2, 0),
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,353 @@
+use crate::{BoundingBox, Connectivity, Direction, Exporter, Grid, Point, Renderer, Rgb};
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Index;
+use std::path::Path;
+
+/// The most cells a sparse grid's bounding box may cover when converted into a dense grid, which
+/// is enough for a 4096x4096 image.
+pub const MAX_DENSE_CELLS: usize = 1 << 24;
+
+/// An unbounded grid holding only the cells that have been set, indexed by signed `(x, y)` with
+/// `y` growing downwards like [`Grid`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point<isize>, T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, point: Point<isize>) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn get(&self, point: Point<isize>) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point<isize>) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    /// Sets a cell, returning its previous value.
+    pub fn insert(&mut self, point: Point<isize>, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: Point<isize>) -> Option<T> {
+        self.cells.remove(&point)
+    }
+
+    /// Iterates over the set cells and their positions, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<isize>, &T)> {
+        self.cells.iter().map(|(point, cell)| (*point, cell))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point<isize>> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// Iterates over every neighbour of a point, whether or not it's set, skipping any beyond the
+    /// range of `isize`.
+    pub fn neighbors(
+        &self,
+        point: Point<isize>,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = (Direction, Point<isize>)> {
+        connectivity
+            .directions()
+            .iter()
+            .filter_map(move |direction| Some((*direction, point.checked_step(*direction)?)))
+    }
+
+    /// Iterates over the neighbours of a point that are set, along with their values.
+    pub fn occupied_neighbors(
+        &self,
+        point: Point<isize>,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = (Direction, Point<isize>, &T)> {
+        self.neighbors(point, connectivity)
+            .filter_map(|(direction, n)| self.get(n).map(|cell| (direction, n, cell)))
+    }
+
+    /// The inclusive corners of the smallest rectangle containing every set cell.
+    pub fn bounding_box(&self) -> Option<BoundingBox<isize>> {
+        BoundingBox::of(self.points())
+    }
+
+    /// The top left cell of the bounding box and its width and height, failing if it covers more
+    /// than [`MAX_DENSE_CELLS`].
+    fn dense_size(&self) -> Result<Option<(Point<isize>, usize, usize)>> {
+        let Some(bounds) = self.bounding_box() else {
+            return Ok(None);
+        };
+
+        let size = |min: isize, max: isize| max.abs_diff(min).checked_add(1);
+        let (width, height) = size(bounds.min.x, bounds.max.x)
+            .zip(size(bounds.min.y, bounds.max.y))
+            .ok_or_else(|| anyhow!("{:?} is too large to make dense", bounds))?;
+
+        match width.checked_mul(height) {
+            Some(cells) if cells <= MAX_DENSE_CELLS => Ok(Some((bounds.min, width, height))),
+            _ => Err(anyhow!(
+                "A {}x{} bounding box is too large to make dense, the limit is {} cells",
+                width,
+                height,
+                MAX_DENSE_CELLS
+            )),
+        }
+    }
+
+    /// Maps every position within the bounding box into a dense grid, along with the position of
+    /// its top left cell. Subtract that origin from a point to find its cell in the dense grid.
+    /// Fails if the bounding box covers more than [`MAX_DENSE_CELLS`].
+    pub fn map_dense<'a, U>(
+        &'a self,
+        mut f: impl FnMut(Option<&'a T>) -> U,
+    ) -> Result<(Grid<U>, Point<isize>)> {
+        let Some((min, width, height)) = self.dense_size()? else {
+            return Ok((Grid::default(), Point::new(0, 0)));
+        };
+
+        let grid = Grid::from_fn(width, height, |p| {
+            f(self.get(Point::new(min.x + p.x as isize, min.y + p.y as isize)))
+        });
+
+        Ok((grid, min))
+    }
+
+    /// Converts into a dense grid covering the bounding box, filling unset cells with `empty`.
+    pub fn to_dense(&self, empty: T) -> Result<(Grid<T>, Point<isize>)>
+    where
+        T: Clone,
+    {
+        self.map_dense(|cell| cell.unwrap_or(&empty).clone())
+    }
+
+    /// A renderer for the bounding box, drawing unset cells as `empty`, along with the origin to
+    /// subtract from points before adding them as layers.
+    pub fn renderer(&self, empty: char) -> Result<(Renderer, Point<isize>)>
+    where
+        for<'a> char: From<&'a T>,
+    {
+        let (chars, origin) = self.map_dense(|cell| cell.map_or(empty, char::from))?;
+        Ok((Renderer::from_chars(chars), origin))
+    }
+
+    /// Writes an image of the bounding box, colouring unset cells with `color(None)`. Convert with
+    /// [`SparseGrid::map_dense`] and use an [`Exporter`] directly to add overlays.
+    pub fn export(&self, path: impl AsRef<Path>, color: impl Fn(Option<&T>) -> Rgb) -> Result<()> {
+        let (grid, _) = self.map_dense(|cell| cell)?;
+        let exporter = Exporter::new(&grid, |cell| color(*cell));
+        exporter.save(path)
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Point<isize>> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, index: Point<isize>) -> &Self::Output {
+        self.get(index)
+            .unwrap_or_else(|| panic!("{:?} is not set", index))
+    }
+}
+
+impl<T> FromIterator<(Point<isize>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point<isize>, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<(Point<isize>, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point<isize>, T)>>(&mut self, iter: I) {
+        self.cells.extend(iter)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Converts the cells matching the predicate into a sparse grid at the same positions.
+    pub fn to_sparse(&self, mut include: impl FnMut(&T) -> bool) -> SparseGrid<T> {
+        self.iter()
+            .filter(|(_, cell)| include(cell))
+            .map(|(point, cell)| (Point::new(point.x as isize, point.y as isize), cell.clone()))
+            .collect()
+    }
+}
+
+impl<T: Clone> From<Grid<T>> for SparseGrid<T> {
+    fn from(value: Grid<T>) -> Self {
+        value.to_sparse(|_| true)
+    }
+}
+
+/// Draws the bounding box, with unset cells as `.`, or only says why if it's too large to draw.
+impl<T> Display for SparseGrid<T>
+where
+    for<'a> char: From<&'a T>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (grid, _) = match self.map_dense(|cell| cell.map_or('.', char::from)) {
+            Ok(dense) => dense,
+            Err(e) => return write!(f, "{} cells: {}", self.len(), e),
+        };
+
+        for (y, row) in grid.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            let row: String = row.iter().collect();
+            write!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn test_get_and_insert() {
+        let mut grid = SparseGrid::new();
+
+        assert_eq!(None, grid.insert(Point::new(-5, 3), 'a'));
+        assert_eq!(Some('a'), grid.insert(Point::new(-5, 3), 'b'));
+        grid.insert(Point::new(1_000_000, -1_000_000), 'c');
+
+        assert_eq!(2, grid.len());
+        assert_eq!('b', grid[Point::new(-5, 3)]);
+        assert_eq!(None, grid.get(Point::new(0, 0)));
+        assert_eq!(
+            Some(BoundingBox {
+                min: Point::new(-5, -1_000_000),
+                max: Point::new(1_000_000, 3),
+            }),
+            grid.bounding_box()
+        );
+
+        assert_eq!(Some('c'), grid.remove(Point::new(1_000_000, -1_000_000)));
+        assert_eq!(
+            Some(BoundingBox {
+                min: Point::new(-5, 3),
+                max: Point::new(-5, 3),
+            }),
+            grid.bounding_box()
+        );
+        assert_eq!(None, SparseGrid::<char>::new().bounding_box());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid: SparseGrid<char> = [(Point::new(0, -1), 'a'), (Point::new(1, 1), 'b')]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            4,
+            grid.neighbors(Point::new(0, 0), Connectivity::Four).count()
+        );
+        assert_eq!(
+            vec![(Direction::Up, Point::new(0, -1), &'a')],
+            grid.occupied_neighbors(Point::new(0, 0), Connectivity::Four)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            2,
+            grid.occupied_neighbors(Point::new(0, 0), Connectivity::Eight)
+                .count()
+        );
+
+        // Neighbours past the edge of isize are skipped rather than overflowing
+        let corner = Point::new(isize::MAX, isize::MIN);
+        assert_eq!(
+            vec![Direction::Down, Direction::Left],
+            grid.neighbors(corner, Connectivity::Four)
+                .map(|(direction, _)| direction)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, grid.neighbors(corner, Connectivity::Eight).count());
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Tile(char);
+
+    impl From<char> for Tile {
+        fn from(value: char) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<&Tile> for char {
+        fn from(value: &Tile) -> Self {
+            value.0
+        }
+    }
+
+    #[test]
+    fn test_dense_conversion() -> Result<()> {
+        let dense = Grid::<Tile>::from_lines(["#..", "..#"])?;
+        let mut sparse = dense.to_sparse(|c| c.0 == '#');
+
+        assert_eq!(2, sparse.len());
+        assert_eq!("#..\n..#", sparse.to_string());
+
+        sparse.insert(Point::new(-1, -1), Tile('#'));
+        let (grid, origin) = sparse.to_dense(Tile('.'))?;
+        assert_eq!(Point::new(-1, -1), origin);
+        assert_eq!(4, grid.width());
+        assert_eq!(3, grid.height());
+        assert_eq!(Tile('#'), grid[Point::new(3, 2)]);
+        assert_eq!("#...\n.#..\n...#", sparse.to_string());
+
+        assert_eq!(6, SparseGrid::from(dense).len());
+
+        // Sparse (0, 0) is drawn at dense (1, 1)
+        let (renderer, origin) = sparse.renderer(' ')?;
+        let cursor = Point::new(-origin.x as usize, -origin.y as usize);
+        let renderer = renderer.ansi(false).glyph_layer([cursor], Color::Red, '*');
+        assert_eq!("#   \n *  \n   #", renderer.render());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_too_large_to_make_dense() {
+        let mut sparse = SparseGrid::new();
+        sparse.insert(Point::new(-1_000_000, 0), Tile('#'));
+        sparse.insert(Point::new(1_000_000, 1_000_000), Tile('#'));
+
+        assert!(sparse.to_dense(Tile('.')).is_err());
+        assert!(sparse.renderer('.').is_err());
+        assert!(sparse
+            .to_string()
+            .starts_with("2 cells: A 2000001x1000001 bounding box"));
+
+        // The width doesn't fit in an isize
+        sparse.insert(Point::new(isize::MIN, 0), Tile('#'));
+        sparse.insert(Point::new(isize::MAX, 0), Tile('#'));
+        assert!(sparse.map_dense(|cell| cell.is_some()).is_err());
+    }
+}